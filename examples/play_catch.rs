@@ -318,15 +318,20 @@ fn on_npc_hold(
 
 fn on_aim_timer(
     mut npcs: Query<(Entity, &mut Npc)>,
+    players: Query<Entity, With<Player>>,
     mut avian_pickup_input_writer: EventWriter<AvianPickupInput>,
 ) {
+    let Ok(player) = players.get_single() else {
+        return;
+    };
     for (entity, mut npc) in &mut npcs {
         if !matches!(npc.state, NpcState::Aiming(..)) || !npc.timer.finished() {
             continue;
         }
         npc.waiting();
+        // Throw the box in an arc so that it lands right at the player.
         avian_pickup_input_writer.send(AvianPickupInput {
-            action: AvianPickupAction::Throw,
+            action: AvianPickupAction::ThrowAt(AvianPickupThrowTarget::Entity(player)),
             actor: entity,
         });
     }
@@ -1,27 +1,38 @@
 //! Module for the actor that can pick up objects.
 
-use std::ops::RangeInclusive;
+use std::{any::TypeId, ops::RangeInclusive, sync::Arc};
 
-use avian3d::{math::Scalar, prelude::*};
+use avian::{math::Scalar, prelude::*};
 use bevy::{
-    ecs::component::{ComponentHooks, StorageType},
+    ecs::{
+        component::{ComponentHooks, StorageType},
+        entity::EntityHashSet,
+    },
     prelude::*,
 };
 
-use crate::{
-    interaction::{HoldError, ShadowParams},
-    prelude::Cooldown,
-};
+use crate::{permission::PickupPermissions, prelude::Cooldown};
 
 pub(super) mod prelude {
     pub use super::{
-        AvianPickupActor, AvianPickupActorHoldConfig, AvianPickupActorPullConfig,
-        AvianPickupActorState, AvianPickupActorThrowConfig,
+        AvianPickupActor, AvianPickupActorAbsorbedProps, AvianPickupActorCooldownConfig,
+        AvianPickupActorHeldProps, AvianPickupActorHoldConfig, AvianPickupActorPullConfig,
+        AvianPickupActorPuntConfig, AvianPickupActorState, AvianPickupActorThrowConfig,
+        AvianPickupHoldMode, AvianPickupPropTargeting, AvianPickupPullArea,
+        AvianPickupPullMassAdjustment, AvianPickupPullMode, AvianPickupRigidBodyFilter,
+        AvianPickupThrowChargeCurve, AvianPickupThrowSpin, CustomPropTargeting, PickupCandidate,
+        PropTargetingFn,
     };
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<(AvianPickupActor, AvianPickupActorState)>();
+    app.register_type::<(
+        AvianPickupActor,
+        AvianPickupActorState,
+        AvianPickupActorHeldProps,
+        AvianPickupActorAbsorbedProps,
+        PickupCandidate,
+    )>();
 }
 
 /// Tag component for an actor that is able to pick up object.
@@ -53,7 +64,9 @@ pub(super) fn plugin(app: &mut App) {
 pub struct AvianPickupActor {
     /// The spatial query filter to use when looking for objects to pick up.\
     /// Note that no matter what this filter says, only entities with a
-    /// [`RigidBody::Dynamic`] will be considered in the first place.\
+    /// [`RigidBody::Dynamic`] or a
+    /// [`DynamicWhileHeld`](crate::prelude::DynamicWhileHeld) will be considered
+    /// in the first place.\
     ///
     /// Default: Include all entities
     pub prop_filter: SpatialQueryFilter,
@@ -68,6 +81,12 @@ pub struct AvianPickupActor {
     /// extent.\
     /// Default: Include all entities
     pub actor_filter: SpatialQueryFilter,
+    /// Filters props by their rigid body, as opposed to [`Self::prop_filter`],
+    /// which looks at the individual colliders.
+    /// Applies to all ways of finding props, as well as to props that are
+    /// already held, which are dropped once they no longer pass the filter.\
+    /// Default: Include all rigid bodies
+    pub rigid_body_filter: AvianPickupRigidBodyFilter,
     /// How far away an object can be interacted with.\
     /// Default: 1.5 m
     ///
@@ -80,12 +99,183 @@ pub struct AvianPickupActor {
     ///
     /// Corresponds to Source's [`physcannon_cone`](https://developer.valvesoftware.com/wiki/Weapon_physcannon#physcannon_cone).
     pub interaction_cone: f32,
+    /// How the prop to pull, pick up or punt is chosen.\
+    /// Default: [`AvianPickupPropTargeting::TraceThenCone`]
+    pub targeting: AvianPickupPropTargeting,
+    /// How much of the actor's own velocity is added to props when throwing or
+    /// dropping them, e.g. so that props thrown while sprinting fly further.
+    /// The velocity is taken from the actor's [`RigidBody`] or, if the actor
+    /// is not a rigid body, from its nearest rigid body ancestor.\
+    /// Default: 0.0
+    pub velocity_inheritance: Scalar,
     /// Configuration that is only used when pulling props to the actor.
     pub pull: AvianPickupActorPullConfig,
     /// Configuration that is only used while holding props.
     pub hold: AvianPickupActorHoldConfig,
     /// Configuration that is only used when throwing props.
     pub throw: AvianPickupActorThrowConfig,
+    /// Configuration that is only used when punting props.
+    pub punt: AvianPickupActorPuntConfig,
+    /// Configuration for how long the actor has to wait between actions.
+    pub cooldown: AvianPickupActorCooldownConfig,
+}
+
+/// Filters props by their rigid body.
+/// Used in [`AvianPickupActor::rigid_body_filter`].
+///
+/// # Example
+///
+/// ```
+/// # use avian_pickup::prelude::*;
+/// # use bevy::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Pickupable;
+///
+/// #[derive(Component)]
+/// struct Bolted;
+///
+/// let filter = AvianPickupRigidBodyFilter::default()
+///     .with::<Pickupable>()
+///     .without::<Bolted>();
+/// ```
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupRigidBodyFilter {
+    /// Only rigid bodies with all of these components pass the filter.
+    /// Add to this with [`AvianPickupRigidBodyFilter::with`].
+    /// Component types cannot be reflected or serialized, so this is empty
+    /// after cloning the filter through reflection or deserializing it.
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub(crate) with: Vec<TypeId>,
+    /// Rigid bodies with any of these components do not pass the filter.
+    /// Add to this with [`AvianPickupRigidBodyFilter::without`].
+    /// Like `with`, this is lost when reflecting or serializing the filter.
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub(crate) without: Vec<TypeId>,
+    /// Rigid bodies that do not pass the filter.\
+    /// Default: None
+    pub excluded_entities: EntityHashSet,
+    /// The range of masses that pass the filter. Held props are judged by
+    /// their mass from before being picked up, not by their
+    /// [`PickupMassOverride`](crate::prelude::PickupMassOverride).\
+    /// Default: `0.0..=Scalar::MAX`
+    pub mass_range: RangeInclusive<Scalar>,
+}
+
+impl AvianPickupRigidBodyFilter {
+    /// Only let rigid bodies with the component `T` pass the filter.\
+    /// Component filters are not reflected or serialized, so add them again
+    /// after deserializing the filter, e.g. in a system reacting to
+    /// `Added<AvianPickupActor>`.
+    pub fn with<T: Component>(mut self) -> Self {
+        self.with.push(TypeId::of::<T>());
+        self
+    }
+
+    /// Do not let rigid bodies with the component `T` pass the filter.\
+    /// Like [`AvianPickupRigidBodyFilter::with`], this is not reflected or
+    /// serialized.
+    pub fn without<T: Component>(mut self) -> Self {
+        self.without.push(TypeId::of::<T>());
+        self
+    }
+
+    /// Do not let the given rigid bodies pass the filter.
+    pub fn with_excluded_entities(mut self, entities: impl IntoIterator<Item = Entity>) -> Self {
+        self.excluded_entities.extend(entities);
+        self
+    }
+
+    /// Only let rigid bodies with a mass in the given range pass the filter.
+    pub fn with_mass_range(mut self, mass_range: RangeInclusive<Scalar>) -> Self {
+        self.mass_range = mass_range;
+        self
+    }
+}
+
+impl Default for AvianPickupRigidBodyFilter {
+    fn default() -> Self {
+        Self {
+            with: Vec::new(),
+            without: Vec::new(),
+            excluded_entities: default(),
+            mass_range: 0.0..=Scalar::MAX,
+        }
+    }
+}
+
+/// How the prop to pull, pick up or punt is chosen.
+/// Used in [`AvianPickupActor::targeting`].
+///
+/// Strategies looking straight ahead search up to
+/// [`AvianPickupActor::interaction_distance`] when pulling, strategies looking
+/// around the actor search up to
+/// [`AvianPickupActorHoldConfig::distance_to_allow_holding`].
+/// When punting, both search up to [`AvianPickupActorPuntConfig::distance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AvianPickupPropTargeting {
+    /// Only consider the prop straight ahead of the actor.
+    Trace,
+    /// Only consider the nearest prop in front of the actor within
+    /// [`AvianPickupActor::interaction_cone`].
+    Cone,
+    /// Consider the prop straight ahead of the actor, falling back to the
+    /// nearest prop in front of it, like Half-Life 2 does.
+    #[default]
+    TraceThenCone,
+    /// Consider the nearest prop around the actor, no matter where the actor
+    /// is looking. Useful for e.g. VR hands.
+    NearestInSphere,
+    /// Consider the prop that is closest to the actor's crosshair, i.e. the one
+    /// with the smallest angle to the actor's forward direction within
+    /// [`AvianPickupActor::interaction_cone`].
+    ClosestToCrosshair,
+    /// Let the actor's [`CustomPropTargeting`] decide.
+    Custom,
+}
+
+/// The function signature used by [`CustomPropTargeting`].
+pub type PropTargetingFn =
+    dyn Fn(&SpatialQuery, Transform, Scalar, &AvianPickupActor) -> Option<Entity> + Send + Sync;
+
+/// Insert this on an actor using [`AvianPickupPropTargeting::Custom`] to
+/// choose the prop it targets yourself.\
+/// The function is called with the spatial query, the actor's global
+/// transform, the maximum distance to search and the actor's configuration.
+/// It should return the collider entity of the targeted prop, if any.
+#[derive(Component, Clone)]
+pub struct CustomPropTargeting(pub Arc<PropTargetingFn>);
+
+impl CustomPropTargeting {
+    /// Creates a new [`CustomPropTargeting`] from the given function.
+    pub fn new(
+        targeting: impl Fn(&SpatialQuery, Transform, Scalar, &AvianPickupActor) -> Option<Entity>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self(Arc::new(targeting))
+    }
+}
+
+impl std::fmt::Debug for CustomPropTargeting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CustomPropTargeting").finish_non_exhaustive()
+    }
 }
 
 /// Configuration that is only used when pulling props to the actor.
@@ -99,7 +289,8 @@ pub struct AvianPickupActor {
 )]
 pub struct AvianPickupActorPullConfig {
     /// How much impulse to be used when pulling objects to the player.
-    /// This is applied every 0.1 seconds.\
+    /// This is applied every 0.1 seconds.
+    /// Only used by [`AvianPickupPullMode::Impulse`].\
     /// Default: 100.0 Ns
     ///
     /// Corresponds to Source's [`physcannon_pullforce`](https://developer.valvesoftware.com/wiki/Weapon_physcannon#physcannon_pullforce).
@@ -109,6 +300,100 @@ pub struct AvianPickupActorPullConfig {
     ///
     /// Corresponds to Source's [`physcannon_maxmass`](https://developer.valvesoftware.com/wiki/Weapon_physcannon#physcannon_maxmass).
     pub max_prop_mass: Scalar,
+    /// Whether pulling a prop that is held by another actor tries to steal it.
+    /// The actor wins if its [`AvianPickupActorHoldConfig::grip_strength`]
+    /// is greater than the combined grip strength of everyone holding the prop.
+    /// The losers are forced to drop the prop, after which it can be pulled as
+    /// usual. Props with [`CooperativeHold`](crate::prop::CooperativeHold)
+    /// are joined instead of stolen.\
+    /// Default: `false`
+    pub can_steal: bool,
+    /// How pulled props are moved towards the actor.\
+    /// Default: [`AvianPickupPullMode::Impulse`]
+    pub mode: AvianPickupPullMode,
+    /// How the pull impulse or force is scaled depending on the prop's mass.\
+    /// Default: [`AvianPickupPullMassAdjustment::HalfLife2`]
+    pub mass_adjustment: AvianPickupPullMassAdjustment,
+    /// If greater than 0, pulled props are not dragged along the floor in a
+    /// straight line. Instead, they are first lifted until their center is
+    /// this high above the ground, as determined by
+    /// [`AvianPickupActor::obstacle_filter`], and then pulled along an arc
+    /// towards the actor. Gravity is compensated for while pulling them.\
+    /// Default: 0.0 m
+    pub lift_height: Scalar,
+    /// If set, pulling affects all eligible props in an area at once instead
+    /// of only the one the actor is facing, like a vacuum.
+    /// Props that get within
+    /// [`AvianPickupActorHoldConfig::distance_to_allow_holding`] are not held,
+    /// but absorbed into the actor's [`AvianPickupActorAbsorbedProps`].\
+    /// Default: `None`
+    pub area: Option<AvianPickupPullArea>,
+}
+
+/// The area in which props are pulled.
+/// Used in [`AvianPickupActorPullConfig::area`].
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupPullArea {
+    /// The radius of the sphere around the actor in which props are pulled.\
+    /// Default: 5.0 m
+    pub radius: Scalar,
+    /// If set, only props within this cone are pulled.
+    /// Works like [`AvianPickupActor::interaction_cone`].
+    /// `None` means that props are pulled from all directions.\
+    /// Default: `Some(0.7)`
+    pub cone: Option<Scalar>,
+    /// The maximum number of props that are pulled at once.
+    /// Props closer to the actor are preferred.\
+    /// Default: 8
+    pub max_props: usize,
+}
+
+impl Default for AvianPickupPullArea {
+    fn default() -> Self {
+        Self {
+            radius: 5.0,
+            cone: Some(0.7),
+            max_props: 8,
+        }
+    }
+}
+
+/// How pulled props are moved towards the actor.
+/// Used in [`AvianPickupActorPullConfig::mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AvianPickupPullMode {
+    /// Apply [`AvianPickupActorPullConfig::impulse`] through an
+    /// [`ExternalImpulse`] every time
+    /// [`AvianPickupActorCooldownConfig::pull`] has passed, like Half-Life 2
+    /// does. Looks choppy at high framerates or with slowed down time.
+    #[default]
+    Impulse,
+    /// Apply a continuous [`ExternalForce`] every physics step for as long as
+    /// the actor is pulling. This overwrites any other force set on the prop
+    /// in the meantime. [`AvianPickupActorCooldownConfig::pull`] is not used.
+    Force {
+        /// The force in N to pull props with, scaled by
+        /// [`AvianPickupActorPullConfig::mass_adjustment`].
+        /// `1000.0` is about as strong as the default impulse.
+        force: Scalar,
+        /// The speed in m/s towards the actor at which the force stops
+        /// accelerating the prop. The force is gradually reduced as the
+        /// prop gets closer to this speed.
+        /// `None` means that the speed is not limited.
+        max_speed: Option<Scalar>,
+    },
 }
 
 impl Default for AvianPickupActorPullConfig {
@@ -116,6 +401,89 @@ impl Default for AvianPickupActorPullConfig {
         Self {
             impulse: 100.0,
             max_prop_mass: 35.0,
+            can_steal: false,
+            mode: default(),
+            mass_adjustment: default(),
+            lift_height: 0.0,
+            area: None,
+        }
+    }
+}
+
+/// How the pull impulse or force is scaled depending on the prop's mass.
+/// Used in [`AvianPickupActorPullConfig::mass_adjustment`].
+#[derive(Debug, Clone, PartialEq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AvianPickupPullMassAdjustment {
+    /// Props lighter than 50 kg receive proportionally less, so light props
+    /// move about as fast as 50 kg props. Heavier props receive the full
+    /// amount and thus move slower.
+    ///
+    /// Taken from [this snippet](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2607-L2610)
+    #[default]
+    HalfLife2,
+    /// All props receive the full amount, so light props move faster than
+    /// heavy ones.
+    Constant,
+    /// Props receive `reference_mass / mass` times the amount, so that all
+    /// props move as fast as a prop with `reference_mass` kg would.
+    InverseMass {
+        /// The mass in kg of the prop that receives the full amount.
+        reference_mass: Scalar,
+    },
+    /// A table of `(mass, factor)` samples, sorted by mass.
+    /// Masses in between are linearly interpolated,
+    /// masses outside the table use the nearest sample.
+    /// An empty table means [`AvianPickupPullMassAdjustment::Constant`].
+    Samples(Vec<(Scalar, Scalar)>),
+}
+
+impl AvianPickupPullMassAdjustment {
+    /// The factor the pull impulse or force is multiplied by for a prop
+    /// with the given mass in kg.
+    pub fn factor(&self, mass: Scalar) -> Scalar {
+        match self {
+            Self::HalfLife2 => {
+                if mass < 50.0 {
+                    (mass + 0.5) * (1.0 / 50.0)
+                } else {
+                    1.0
+                }
+            }
+            Self::Constant => 1.0,
+            Self::InverseMass { reference_mass } => {
+                if mass > 0.0 {
+                    reference_mass / mass
+                } else {
+                    1.0
+                }
+            }
+            Self::Samples(samples) => {
+                let Some(&(first_mass, first_factor)) = samples.first() else {
+                    return 1.0;
+                };
+                if mass <= first_mass {
+                    return first_factor;
+                }
+                for window in samples.windows(2) {
+                    let [(mass_a, factor_a), (mass_b, factor_b)] = [window[0], window[1]];
+                    if mass <= mass_b {
+                        let t = if mass_b > mass_a {
+                            (mass - mass_a) / (mass_b - mass_a)
+                        } else {
+                            1.0
+                        };
+                        return factor_a.lerp(factor_b, t);
+                    }
+                }
+                // Safety: we returned early if the table is empty.
+                samples.last().unwrap().1
+            }
         }
     }
 }
@@ -144,14 +512,21 @@ pub struct AvianPickupActorHoldConfig {
     /// `max(collider_radius, min_distance)`.\
     /// Default: 0.5 m
     pub min_distance: Scalar,
+    /// How held props are moved towards their target.
+    /// When multiple actors hold the same prop, the mode of the actor that
+    /// picked it up first is used.\
+    /// Default: [`AvianPickupHoldMode::Velocity`]
+    pub mode: AvianPickupHoldMode,
     /// A number >= 0 that indicates how much exponential easing will be applied
-    /// to the held prop's velocity when the actor is moving.\
+    /// to the held prop's velocity when the actor is moving.
+    /// Only used by [`AvianPickupHoldMode::Velocity`].\
     /// A value of 0 means no smoothing, i.e. the prop perfectly follows the
     /// actor's position.\
     /// Default: 1.0
     pub linear_velocity_easing: Scalar,
     /// A number >= 0 that indicates how much exponential easing will be applied
-    /// to the held prop's angular velocity when the actor is rotating.\
+    /// to the held prop's angular velocity when the actor is rotating.
+    /// Only used by [`AvianPickupHoldMode::Velocity`].\
     /// A value of 0 means no smoothing, i.e. the prop perfectly follows the
     /// actor's point of view.\
     /// Default: 1.6
@@ -176,11 +551,65 @@ pub struct AvianPickupActorHoldConfig {
     /// objects could potentially generate *a lot* of force when colliding
     /// with other objects.
     /// The prop's original mass will be restored when the prop is no longer
-    /// being held\
+    /// being held.
+    /// Not used by [`AvianPickupHoldMode::Joint`] and
+    /// [`AvianPickupHoldMode::PdController`], as they respect the prop's mass.\
     /// Can be overridden by adding a
     /// [`PickupMassOverride`](crate::prop::PickupMassOverride) to the prop.\
     /// Default: 1 kg
     pub temporary_prop_mass: Scalar,
+    /// The maximum number of props the actor can hold at the same time.
+    /// While the actor holds fewer props than this, it can keep pulling
+    /// new ones with [`AvianPickupAction::Pull`](crate::prelude::AvianPickupAction::Pull).\
+    /// Default: 1
+    pub max_held_props: usize,
+    /// The horizontal distance in meters between the targets of props held at
+    /// the same time. The props are lined up along the actor's right
+    /// vector, centered on its forward vector.
+    /// Has no effect when only a single prop is held.\
+    /// Default: 0.6 m
+    pub held_prop_spacing: Scalar,
+    /// How firmly the actor holds on to its props when another actor tries to
+    /// steal them. See [`AvianPickupActorPullConfig::can_steal`].\
+    /// Default: 1.0
+    pub grip_strength: Scalar,
+    /// The distance in meters between a held prop and its target at which the
+    /// prop is forcefully dropped, e.g. because it got stuck behind terrain.
+    /// The distance is smoothed over time, see [`HoldError`](crate::prop::HoldError).\
+    /// Can be overridden by adding a
+    /// [`MaxHoldErrorOverride`](crate::prop::MaxHoldErrorOverride) to the prop.\
+    /// Default: 0.3048 m (12 inches, like in Half-Life 2)
+    pub max_error: Scalar,
+    /// The time in seconds after picking up a prop before the distance to its
+    /// target starts counting towards [`max_error`](Self::max_error).
+    /// This gives the prop some time to reach its target in the first place.\
+    /// Can be overridden by adding a
+    /// [`HoldErrorWarmupOverride`](crate::prop::HoldErrorWarmupOverride) to the
+    /// prop.\
+    /// Default: 1.0 s
+    pub error_warmup: Scalar,
+    /// The maximum time in seconds that counts towards a single update of the
+    /// smoothed error. The smoothed error moves towards the current distance
+    /// by the time since its last update, capped at this value. Clamped to
+    /// `0.0..=1.0` s, as the smoothed error would overshoot otherwise.\
+    /// Can be overridden by adding a
+    /// [`MaxHoldErrorTimeOverride`](crate::prop::MaxHoldErrorTimeOverride) to
+    /// the prop.\
+    /// Default: 1.0 s
+    pub max_error_time: Scalar,
+    /// The factor the error is multiplied with when the prop would need to
+    /// move faster than its maximum speed to reach its target. This prevents
+    /// forced drops when the actor is simply moving fast.\
+    /// Default: 0.5
+    pub fast_error_scale: Scalar,
+    /// The fraction of [`max_error`](Self::max_error) at which a
+    /// [`HoldStrained`](crate::output::HoldStrained) is sent, e.g. to play a
+    /// creaking sound before the prop slips.\
+    /// Can be overridden by adding a
+    /// [`HoldStrainedFractionOverride`](crate::prop::HoldStrainedFractionOverride)
+    /// to the prop.\
+    /// Default: 0.75
+    pub strained_fraction: Scalar,
 }
 
 impl Default for AvianPickupActorHoldConfig {
@@ -188,11 +617,164 @@ impl Default for AvianPickupActorHoldConfig {
         Self {
             distance_to_allow_holding: 1.0,
             min_distance: 0.5,
+            mode: default(),
             linear_velocity_easing: 1.0,
             angular_velocity_easing: 1.6,
             pitch_range: (-75.0_f32).to_radians()..=75.0_f32.to_radians(),
             preferred_distance: 1.25,
             temporary_prop_mass: 1.0,
+            max_held_props: 1,
+            held_prop_spacing: 0.6,
+            grip_strength: 1.0,
+            max_error: 0.3048,
+            error_warmup: 1.0,
+            max_error_time: 1.0,
+            fast_error_scale: 0.5,
+            strained_fraction: 0.75,
+        }
+    }
+}
+
+/// The way held props are moved towards their target.
+/// Used in [`AvianPickupActorHoldConfig::mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AvianPickupHoldMode {
+    /// Overwrite the prop's velocity every physics step so that it eases
+    /// towards its target. This is what Half-Life 2 does.
+    /// Since the velocity is set independent of the prop's mass, the prop is
+    /// made lighter while held, see
+    /// [`AvianPickupActorHoldConfig::temporary_prop_mass`].
+    #[default]
+    Velocity,
+    /// Attach the prop with a [`FixedJoint`] to a kinematic anchor that
+    /// follows the target. The prop keeps its mass, so it interacts physically
+    /// with its surroundings, e.g. heavy props lag behind and can push other
+    /// props around, while props stacked on it weigh it down.
+    Joint {
+        /// The compliance of the joint, i.e. the inverse of its stiffness.
+        /// Higher values make the prop follow more loosely.\
+        /// Default: 0.0001
+        compliance: Scalar,
+        /// The linear velocity damping of the joint.\
+        /// Default: 5.0
+        linear_damping: Scalar,
+        /// The angular velocity damping of the joint.\
+        /// Default: 5.0
+        angular_damping: Scalar,
+    },
+    /// Push the prop towards its target with a proportional-derivative
+    /// controller through [`ExternalForce`] and [`ExternalTorque`].
+    /// The prop keeps its mass, so heavy props visibly lag behind and sway,
+    /// which feels more like telekinesis than like holding something in your
+    /// hands.
+    PdController {
+        /// The force in N per meter of distance to the target.\
+        /// Default: 1000.0 N/m
+        stiffness: Scalar,
+        /// The force in N per m/s of linear velocity working against the
+        /// prop's movement.\
+        /// Default: 100.0 Ns/m
+        damping: Scalar,
+        /// The maximum force in N that will be applied to the prop.\
+        /// Default: 1500.0 N
+        max_force: Scalar,
+        /// The torque in Nm per radian of rotation to the target.\
+        /// Default: 50.0 Nm/rad
+        angular_stiffness: Scalar,
+        /// The torque in Nm per rad/s of angular velocity working against the
+        /// prop's rotation.\
+        /// Default: 10.0 Nms/rad
+        angular_damping: Scalar,
+        /// The maximum torque in Nm that will be applied to the prop.\
+        /// Default: 200.0 Nm
+        max_torque: Scalar,
+    },
+}
+
+impl AvianPickupHoldMode {
+    /// A [`AvianPickupHoldMode::Joint`] with default values.
+    pub fn joint() -> Self {
+        Self::Joint {
+            compliance: 0.0001,
+            linear_damping: 5.0,
+            angular_damping: 5.0,
+        }
+    }
+
+    /// A [`AvianPickupHoldMode::PdController`] with default values.
+    pub fn pd_controller() -> Self {
+        Self::PdController {
+            stiffness: 1000.0,
+            damping: 100.0,
+            max_force: 1500.0,
+            angular_stiffness: 50.0,
+            angular_damping: 10.0,
+            max_torque: 200.0,
+        }
+    }
+
+    /// Whether this mode respects the prop's mass, as opposed to overwriting
+    /// its velocity.
+    pub fn respects_mass(self) -> bool {
+        !matches!(self, Self::Velocity)
+    }
+}
+
+/// Configuration for how long the actor has to wait between actions.
+/// The current state of the cooldowns can be read from the actor's
+/// [`Cooldown`].
+/// Used in [`AvianPickupActor::cooldown`].
+///
+/// Timings taken from [`CWeaponPhysCannon::SecondaryAttack`](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2284)
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupActorCooldownConfig {
+    /// The time in seconds after throwing a prop until the actor can pull
+    /// again.\
+    /// Default: 0.5 s
+    pub throw: Scalar,
+    /// The time in seconds after dropping a prop until the actor can pull
+    /// again.\
+    /// Default: 0.5 s
+    pub drop: Scalar,
+    /// The time in seconds after picking up a prop until the actor can drop
+    /// it again.\
+    /// Default: 0.4 s
+    pub hold: Scalar,
+    /// The time in seconds between two pull impulses.\
+    /// Default: 0.1 s
+    pub pull: Scalar,
+    /// The time in seconds after punting a prop until the actor can punt or
+    /// pull again.\
+    /// Default: 0.5 s
+    pub punt: Scalar,
+}
+
+impl Default for AvianPickupActorCooldownConfig {
+    fn default() -> Self {
+        Self {
+            // Happens to be the same as `drop`, but that's a coincidence.
+            throw: 0.5,
+            drop: 0.5,
+            // Sneakily updated in two places:
+            // - [+ 0.5](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2316)
+            // - [+ 0.4](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2438)
+            // Let's use just 0.4, that feels nicer.
+            hold: 0.4,
+            pull: 0.1,
+            // Taken from `CWeaponPhysCannon::PuntVPhysics`
+            punt: 0.5,
         }
     }
 }
@@ -228,6 +810,21 @@ pub struct AvianPickupActorThrowConfig {
     /// to the prop.\
     /// Default: 0.0 rad/s to 1.0 rad/s
     pub angular_speed_range: RangeInclusive<Scalar>,
+    /// Around which axis thrown props spin.\
+    /// Can be overridden by adding a
+    /// [`ThrownSpinAxisOverride`](crate::prop::ThrownSpinAxisOverride)
+    /// to the prop.\
+    /// Default: [`AvianPickupThrowSpin::Random`]
+    pub spin: AvianPickupThrowSpin,
+    /// The time in seconds it takes to fully charge a throw started with
+    /// [`AvianPickupAction::StartChargingThrow`](crate::prelude::AvianPickupAction::StartChargingThrow).
+    /// A throw released before that will be thrown with a speed between
+    /// `linear_speed_range.start()` and the speed a regular throw would have.\
+    /// Default: 1.0 s
+    pub charge_duration: Scalar,
+    /// How the speed of a charged throw grows while charging.\
+    /// Default: [`AvianPickupThrowChargeCurve::Linear`]
+    pub charge_curve: AvianPickupThrowChargeCurve,
 }
 
 impl Default for AvianPickupActorThrowConfig {
@@ -236,6 +833,101 @@ impl Default for AvianPickupActorThrowConfig {
             cutoff_mass_for_slowdown: 20.0,
             linear_speed_range: 0.0..=5.0,
             angular_speed_range: 0.0..=1.0,
+            spin: default(),
+            charge_duration: 1.0,
+            charge_curve: default(),
+        }
+    }
+}
+
+/// Configuration that is only used when punting props.
+/// Used in [`AvianPickupActor::punt`].
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupActorPuntConfig {
+    /// How far away a prop can be punted.\
+    /// Default: 3.0 m
+    pub distance: Scalar,
+    /// The impulse applied to punted props in the direction the actor is
+    /// facing. Props lighter than `mass_falloff.start()` receive
+    /// proportionally less, so that they are not launched at absurd speeds.\
+    /// Default: 500.0 Ns
+    pub impulse: Scalar,
+    /// Props heavier than `mass_falloff.start()` receive less impulse the
+    /// heavier they are. Props at or above `mass_falloff.end()` cannot be
+    /// punted at all.\
+    /// Default: 50.0 kg to 250.0 kg
+    pub mass_falloff: RangeInclusive<Scalar>,
+}
+
+impl Default for AvianPickupActorPuntConfig {
+    fn default() -> Self {
+        Self {
+            distance: 3.0,
+            impulse: 500.0,
+            mass_falloff: 50.0..=250.0,
+        }
+    }
+}
+
+/// Around which axis thrown props spin.
+/// Used in [`AvianPickupActorThrowConfig::spin`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AvianPickupThrowSpin {
+    /// Spin around a random axis with a random speed in
+    /// [`AvianPickupActorThrowConfig::angular_speed_range`].
+    #[default]
+    Random,
+    /// Spin around the given axis in the actor's local space with
+    /// `angular_speed_range.end()`.
+    /// For example, `Vec3::X` gives frisbee-like backspin around the actor's
+    /// right vector.\
+    /// In 2D, only the Z component is used.
+    Axis(Vec3),
+}
+
+/// How the speed of a charged throw grows while charging.
+/// Used in [`AvianPickupActorThrowConfig::charge_curve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AvianPickupThrowChargeCurve {
+    /// The speed grows evenly.
+    #[default]
+    Linear,
+    /// The speed grows slowly at first and quickly at the end.
+    EaseIn,
+    /// The speed grows quickly at first and slowly at the end.
+    EaseOut,
+    /// The speed grows slowly at the start and the end.
+    SmoothStep,
+}
+
+impl AvianPickupThrowChargeCurve {
+    /// Maps the charged fraction of the time in [0, 1] to the fraction of the
+    /// speed in [0, 1].
+    pub fn sample(self, fraction: Scalar) -> Scalar {
+        let x = fraction.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => x,
+            Self::EaseIn => x * x,
+            Self::EaseOut => 1.0 - (1.0 - x) * (1.0 - x),
+            Self::SmoothStep => x * x * (3.0 - 2.0 * x),
         }
     }
 }
@@ -260,7 +952,11 @@ pub enum AvianPickupActorState {
     /// The object is still too far away to be picked up,
     /// so we're pulling it closer.
     Pulling(Entity),
-    /// The actor is holding an object.
+    /// The actor is holding at least one object.
+    /// The entity is the prop that was picked up most recently, which is
+    /// the one that [`AvianPickupAction::Throw`](crate::prelude::AvianPickupAction::Throw)
+    /// and [`AvianPickupAction::Drop`](crate::prelude::AvianPickupAction::Drop)
+    /// apply to. See [`AvianPickupActorHeldProps`] for all held props.
     Holding(Entity),
 }
 
@@ -281,17 +977,104 @@ impl AvianPickupActorState {
     }
 }
 
+/// The props currently held by an [`AvianPickupActor`], in the order they were
+/// picked up. This component is automatically added to the entity holding the
+/// [`AvianPickupActor`], do not add or remove it.\
+/// The number of props is limited by
+/// [`AvianPickupActorHoldConfig::max_held_props`].
+#[derive(Debug, Clone, PartialEq, Eq, Component, Default, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupActorHeldProps(pub(crate) Vec<Entity>);
+
+impl AvianPickupActorHeldProps {
+    /// Iterates over the held props, starting with the one picked up first.
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Returns the number of held props.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no props are held.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if the given prop is held.
+    pub fn contains(&self, prop: Entity) -> bool {
+        self.0.contains(&prop)
+    }
+
+    /// Returns the prop that was picked up most recently, if any.
+    pub fn last(&self) -> Option<Entity> {
+        self.0.last().copied()
+    }
+}
+
+/// The props absorbed by an [`AvianPickupActor`] while pulling with
+/// [`AvianPickupActorPullConfig::area`] set, in the order they were absorbed.
+/// This component is automatically added to the entity holding the
+/// [`AvianPickupActor`], do not add or remove it.\
+/// Absorbed props are marked with [`AbsorbedProp`](crate::prop::AbsorbedProp)
+/// and otherwise left untouched, so you probably want to despawn or hide them
+/// when receiving a [`PropAbsorbed`](crate::output::PropAbsorbed).
+/// Feel free to take props out of the queue, e.g. to shoot them back out.
+#[derive(Debug, Clone, PartialEq, Eq, Component, Default, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupActorAbsorbedProps(pub Vec<Entity>);
+
+/// The prop an [`AvianPickupActor`] would pick up or pull if it received
+/// [`AvianPickupAction::Pull`](crate::prelude::AvianPickupAction::Pull)
+/// right now. Only set while the actor is
+/// [`AvianPickupActorState::Idle`].\
+/// This component is opt-in: insert it on the actor to have it kept up to
+/// date, e.g. to highlight the prop or to show a "Press E" prompt.
+/// Listen to [`CandidateChanged`](crate::output::CandidateChanged) to react to
+/// changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Default, Reflect)]
+#[reflect(Debug, Component, PartialEq, Hash, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PickupCandidate(pub(crate) Option<Entity>);
+
+impl PickupCandidate {
+    /// Returns the rigid body entity of the candidate prop, if any.
+    pub fn get(&self) -> Option<Entity> {
+        self.0
+    }
+}
+
 impl Default for AvianPickupActor {
     fn default() -> Self {
         Self {
             prop_filter: default(),
             obstacle_filter: default(),
             actor_filter: default(),
+            rigid_body_filter: default(),
             interaction_distance: 1.5,
             interaction_cone: 0.92,
+            targeting: default(),
+            velocity_inheritance: 0.0,
             pull: default(),
             hold: default(),
             throw: default(),
+            punt: default(),
+            cooldown: default(),
         }
     }
 }
@@ -305,9 +1088,65 @@ impl Component for AvianPickupActor {
             commands.entity(targeted_entity).insert((
                 AvianPickupActorState::default(),
                 Cooldown::default(),
-                HoldError::default(),
-                ShadowParams::default(),
+                AvianPickupActorHeldProps::default(),
+                AvianPickupActorAbsorbedProps::default(),
+                PickupPermissions::default(),
             ));
         });
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn charge_curves_span_unit_range() {
+        use AvianPickupThrowChargeCurve::*;
+        for curve in [Linear, EaseIn, EaseOut, SmoothStep] {
+            assert_eq!(curve.sample(0.0), 0.0);
+            assert_eq!(curve.sample(1.0), 1.0);
+            // Out of range fractions are clamped.
+            assert_eq!(curve.sample(-1.0), 0.0);
+            assert_eq!(curve.sample(2.0), 1.0);
+        }
+        assert_eq!(Linear.sample(0.5), 0.5);
+        assert_eq!(EaseIn.sample(0.5), 0.25);
+        assert_eq!(EaseOut.sample(0.5), 0.75);
+        assert_eq!(SmoothStep.sample(0.5), 0.5);
+        assert!(SmoothStep.sample(0.25) < Linear.sample(0.25));
+        assert!(SmoothStep.sample(0.75) > Linear.sample(0.75));
+    }
+
+    #[test]
+    fn mass_adjustment_interpolates_samples() {
+        let adjustment =
+            AvianPickupPullMassAdjustment::Samples(vec![(10.0, 0.5), (20.0, 1.0), (40.0, 2.0)]);
+        assert_eq!(adjustment.factor(10.0), 0.5);
+        assert_eq!(adjustment.factor(15.0), 0.75);
+        assert_eq!(adjustment.factor(20.0), 1.0);
+        assert_eq!(adjustment.factor(30.0), 1.5);
+        assert_eq!(adjustment.factor(40.0), 2.0);
+        // Masses outside the table use the nearest sample.
+        assert_eq!(adjustment.factor(0.0), 0.5);
+        assert_eq!(adjustment.factor(100.0), 2.0);
+
+        let empty = AvianPickupPullMassAdjustment::Samples(vec![]);
+        assert_eq!(empty.factor(25.0), 1.0);
+    }
+
+    #[test]
+    fn mass_adjustment_factors() {
+        let half_life_2 = AvianPickupPullMassAdjustment::HalfLife2;
+        assert!((half_life_2.factor(24.5) - 0.5).abs() < 1e-6);
+        assert_eq!(half_life_2.factor(50.0), 1.0);
+        assert_eq!(half_life_2.factor(200.0), 1.0);
+
+        let inverse_mass = AvianPickupPullMassAdjustment::InverseMass {
+            reference_mass: 10.0,
+        };
+        assert_eq!(inverse_mass.factor(5.0), 2.0);
+        assert_eq!(inverse_mass.factor(20.0), 0.5);
+        assert_eq!(inverse_mass.factor(0.0), 1.0);
+    }
+}
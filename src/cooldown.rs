@@ -1,70 +1,103 @@
+//! Module for the cooldowns that prevent actors from spamming actions.
+
 use std::time::Duration;
 
-use avian3d::prelude::*;
-use bevy::{prelude::*, utils::hashbrown::HashMap};
+use avian::prelude::*;
+use bevy::prelude::*;
 
-use crate::{prelude::AvianPickupAction, AvianPickupSystem};
+use crate::{
+    prelude::{AvianPickupAction, AvianPickupActorCooldownConfig},
+    AvianPickupSystem,
+};
 
 pub(super) mod prelude {
-    pub(crate) use super::Cooldown;
+    pub use super::Cooldown;
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(PhysicsSchedule, tick.in_set(AvianPickupSystem::TickTimers));
+    app.register_type::<Cooldown>()
+        .add_systems(PhysicsSchedule, tick.in_set(AvianPickupSystem::TickTimers));
 }
 
-/// Timings taken from [`CWeaponPhysCannon::SecondaryAttack`](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2284)
-#[derive(Debug, Clone, Component)]
-pub(crate) struct Cooldown(HashMap<AvianPickupAction, Timer>);
-
-impl Default for Cooldown {
-    fn default() -> Self {
-        let map = AvianPickupAction::iter()
-            .map(|action| (action, default()))
-            .collect();
-
-        Self(map)
-    }
+/// The time until an [`AvianPickupActor`](crate::prelude::AvianPickupActor)
+/// can perform an [`AvianPickupAction`] again.
+/// This component is automatically added to the entity holding the
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor), do not add or
+/// remove it. It is only meant to be read, e.g. to show a reticle on the HUD.
+/// The durations are configured in
+/// [`AvianPickupActor::cooldown`](crate::prelude::AvianPickupActor::cooldown).
+///
+/// Like in Half-Life 2, each action mostly delays a *different* action:
+/// - Throwing, dropping and punting a prop delay [`AvianPickupAction::Pull`].
+/// - Picking up a prop delays [`AvianPickupAction::Drop`].
+/// - Punting also delays [`AvianPickupAction::Punt`].
+/// - Pulling delays the next pull impulse.
+///
+/// Throwing is never on cooldown.
+#[derive(Debug, Clone, PartialEq, Component, Default, Reflect)]
+#[reflect(Debug, Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct Cooldown {
+    drop: Timer,
+    pull: Timer,
+    punt: Timer,
 }
 
 impl Cooldown {
-    fn get(&self, action: &AvianPickupAction) -> &Timer {
-        // Safety: all actions are always present in the map as we initialize them in `default`.
-        self.0.get(action).unwrap()
+    /// The timer gating the action, if any.
+    fn get(&self, action: &AvianPickupAction) -> Option<&Timer> {
+        match action {
+            AvianPickupAction::Throw
+            | AvianPickupAction::ThrowProp(..)
+            | AvianPickupAction::StartChargingThrow
+            | AvianPickupAction::ReleaseThrow
+            | AvianPickupAction::ThrowAt(..) => None,
+            AvianPickupAction::Drop | AvianPickupAction::DropProp(..) => Some(&self.drop),
+            AvianPickupAction::Pull => Some(&self.pull),
+            AvianPickupAction::Punt => Some(&self.punt),
+        }
+    }
+
+    /// Whether the given action can be performed again.
+    /// [`AvianPickupAction::DropProp`] shares the cooldown of
+    /// [`AvianPickupAction::Drop`]. Throwing actions are always finished.
+    pub fn finished(&self, action: AvianPickupAction) -> bool {
+        self.get(&action).map_or(true, Timer::finished)
     }
 
-    fn set(&mut self, action: AvianPickupAction, seconds: f32) {
-        self.0
-            .insert(action, Timer::from_seconds(seconds, TimerMode::Once));
+    /// The time until the given action can be performed again.
+    /// Zero for throwing actions, as they are never on cooldown.
+    pub fn remaining(&self, action: AvianPickupAction) -> Duration {
+        self.get(&action).map_or(Duration::ZERO, Timer::remaining)
     }
 
-    pub(crate) fn finished(&self, action: AvianPickupAction) -> bool {
-        self.get(&action).finished()
+    pub(crate) fn throw(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.pull = Timer::from_seconds(config.throw, TimerMode::Once);
     }
 
-    pub(crate) fn throw(&mut self) {
-        // Happens to be the same as `drop`, but that's a coincidence.
-        self.set(AvianPickupAction::Pull, 0.5);
+    pub(crate) fn drop(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.pull = Timer::from_seconds(config.drop, TimerMode::Once);
     }
 
-    pub(crate) fn drop(&mut self) {
-        self.set(AvianPickupAction::Pull, 0.5);
+    pub(crate) fn hold(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.drop = Timer::from_seconds(config.hold, TimerMode::Once);
     }
 
-    pub(crate) fn hold(&mut self) {
-        // Sneakily updated in two places:
-        // - [+ 0.5](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2316)
-        // - [+ 0.4](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2438)
-        // Let's use just 0.4, that feels nicer.
-        self.set(AvianPickupAction::Drop, 0.4);
+    pub(crate) fn pull(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.pull = Timer::from_seconds(config.pull, TimerMode::Once);
     }
 
-    pub(crate) fn pull(&mut self) {
-        self.set(AvianPickupAction::Pull, 0.1);
+    pub(crate) fn punt(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.punt = Timer::from_seconds(config.punt, TimerMode::Once);
+        self.pull = Timer::from_seconds(config.punt, TimerMode::Once);
     }
 
     pub(crate) fn tick(&mut self, time: Duration) {
-        for timer in self.0.values_mut() {
+        for timer in [&mut self.drop, &mut self.pull, &mut self.punt] {
             timer.tick(time);
         }
     }
@@ -3,24 +3,23 @@
 use bevy::{prelude::*, utils::HashSet};
 
 use crate::{
-    interaction::{HoldError, ShadowParams},
-    prelude::{AvianPickupActor, AvianPickupActorState, Cooldown},
-    verb::{SetVerb, Verb},
+    prelude::{AvianPickupActor, AvianPickupActorHeldProps, AvianPickupActorState, Cooldown},
+    verb::{ChargingThrow, SetVerb, Verb},
 };
 
 pub(super) mod prelude {
-    pub use super::{AvianPickupAction, AvianPickupInput};
+    pub use super::{AvianPickupAction, AvianPickupInput, AvianPickupThrowTarget};
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<AvianPickupInput>()
+    app.register_type::<(AvianPickupInput, AvianPickupThrowTarget)>()
         .add_event::<AvianPickupInput>()
         .add_systems(PostUpdate, set_verbs_according_to_input);
 }
 
 /// Event for picking up and throwing objects.
 /// Send this to tell Avian Pickup to do its thing.
-#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[derive(Event, Debug, Clone, Copy, PartialEq, Reflect)]
 #[reflect(Debug, PartialEq)]
 #[cfg_attr(
     feature = "serialize",
@@ -35,8 +34,8 @@ pub struct AvianPickupInput {
 }
 
 /// The kind of input that the [`AvianPickupInput`] represents.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect)]
-#[reflect(Debug, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
 #[cfg_attr(
     feature = "serialize",
     derive(serde::Serialize, serde::Deserialize),
@@ -44,40 +43,84 @@ pub struct AvianPickupInput {
 )]
 pub enum AvianPickupAction {
     /// The left mouse button was just pressed this update.
+    /// Throws the prop that was picked up most recently.
     Throw,
     /// The right mouse button was just pressed this update.
+    /// Drops the prop that was picked up most recently.
     Drop,
     /// The right mouse button was pressed.
     Pull,
+    /// Like [`AvianPickupAction::Throw`], but throws the given held prop.
+    /// Useful when holding multiple props, as configured by
+    /// [`AvianPickupActorHoldConfig::max_held_props`](crate::prelude::AvianPickupActorHoldConfig::max_held_props).
+    ThrowProp(Entity),
+    /// Like [`AvianPickupAction::Drop`], but drops the given held prop.
+    /// Useful when holding multiple props, as configured by
+    /// [`AvianPickupActorHoldConfig::max_held_props`](crate::prelude::AvianPickupActorHoldConfig::max_held_props).
+    DropProp(Entity),
+    /// The throw button was just pressed this update.
+    /// Starts charging a throw of the prop that was picked up most recently.
+    /// The throw happens on [`AvianPickupAction::ReleaseThrow`], with a speed
+    /// depending on how long it was charged, as configured by
+    /// [`AvianPickupActorThrowConfig::charge_duration`](crate::prelude::AvianPickupActorThrowConfig::charge_duration).
+    /// While charging, [`ThrowCharging`](crate::output::ThrowCharging) is sent
+    /// every update.
+    StartChargingThrow,
+    /// The throw button was just released this update.
+    /// Throws the prop charged with [`AvianPickupAction::StartChargingThrow`].
+    ReleaseThrow,
+    /// Like [`AvianPickupAction::Throw`], but instead of throwing the prop
+    /// straight ahead, throws it in an arc so that it lands on the target.
+    /// If the target is too far away, the prop is thrown in the arc that gets
+    /// it closest to the target.
+    /// Useful for NPCs.
+    ThrowAt(AvianPickupThrowTarget),
+    /// The left mouse button was just pressed this update while not holding
+    /// anything.
+    /// Launches the prop in front of the actor without picking it up, like
+    /// the primary fire of the Half-Life 2 gravity gun.
+    /// Does nothing while holding a prop, use [`AvianPickupAction::Throw`]
+    /// instead. Configured by
+    /// [`AvianPickupActor::punt`](crate::prelude::AvianPickupActor::punt).
+    Punt,
 }
 
-impl AvianPickupAction {
-    pub(crate) fn iter() -> impl Iterator<Item = Self> {
-        [Self::Throw, Self::Drop, Self::Pull].iter().copied()
-    }
+/// The target of an [`AvianPickupAction::ThrowAt`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AvianPickupThrowTarget {
+    /// A point in world space.
+    Point(Vec3),
+    /// An entity with a [`GlobalTransform`].
+    /// If the entity has a [`LinearVelocity`](avian::prelude::LinearVelocity),
+    /// the throw leads it so that the prop intercepts it.
+    Entity(Entity),
 }
 
 fn set_verbs_according_to_input(
     mut r_input: EventReader<AvianPickupInput>,
     mut commands: Commands,
-    q_actor: Query<
-        (
-            Entity,
-            Option<&AvianPickupActorState>,
-            Option<&Cooldown>,
-            Has<GlobalTransform>,
-            Has<ShadowParams>,
-            Has<HoldError>,
-        ),
-        With<AvianPickupActor>,
-    >,
+    q_actor: Query<(
+        Entity,
+        &AvianPickupActor,
+        Option<&AvianPickupActorState>,
+        Option<&Cooldown>,
+        Option<&AvianPickupActorHeldProps>,
+        Option<&ChargingThrow>,
+        Has<GlobalTransform>,
+    )>,
 ) {
     let mut unhandled_actors: HashSet<_> = q_actor.iter().map(|(entity, ..)| entity).collect();
     'outer: for &event in r_input.read() {
         let action = event.action;
         let actor = event.actor;
         unhandled_actors.remove(&actor);
-        let Ok((_entity, state, cooldown, has_global_transform, has_shadow, has_error)) =
+        let Ok((_entity, config, state, cooldown, held_props, charging, has_global_transform)) =
             q_actor.get(actor)
         else {
             error!(
@@ -87,11 +130,7 @@ fn set_verbs_according_to_input(
         };
 
         // Doing these checks now so that we can report issues early.
-        let checks = [
-            (has_global_transform, "GlobalTransform"),
-            (has_shadow, "ShadowParams"),
-            (has_error, "HoldError"),
-        ];
+        let checks = [(has_global_transform, "GlobalTransform")];
         for (has_component, component_name) in checks.iter() {
             if !has_component {
                 error!(
@@ -113,6 +152,14 @@ fn set_verbs_according_to_input(
             continue;
         };
 
+        let Some(held_props) = held_props else {
+            error!(
+                "`AvianPickupEvent` was triggered on an entity without `AvianPickupActorHeldProps`. Ignoring."
+            );
+            continue;
+        };
+        let can_hold_more = held_props.len() < config.hold.max_held_props;
+
         let verb = match action {
             AvianPickupAction::Throw
                 if cooldown.finished(AvianPickupAction::Throw)
@@ -121,7 +168,7 @@ fn set_verbs_according_to_input(
                 let AvianPickupActorState::Holding(prop) = state else {
                     unreachable!()
                 };
-                Some(Verb::Throw(prop))
+                Some(Verb::Throw { prop, target: None })
             }
             AvianPickupAction::Drop
                 if matches!(state, AvianPickupActorState::Holding(..))
@@ -135,11 +182,53 @@ fn set_verbs_according_to_input(
                     forced: false,
                 })
             }
+            AvianPickupAction::ThrowProp(prop)
+                if cooldown.finished(AvianPickupAction::Throw) && held_props.contains(prop) =>
+            {
+                Some(Verb::Throw { prop, target: None })
+            }
+            AvianPickupAction::DropProp(prop)
+                if held_props.contains(prop) && cooldown.finished(AvianPickupAction::Drop) =>
+            {
+                Some(Verb::Drop {
+                    prop,
+                    forced: false,
+                })
+            }
+            AvianPickupAction::StartChargingThrow
+                if cooldown.finished(AvianPickupAction::StartChargingThrow)
+                    && matches!(state, AvianPickupActorState::Holding(..)) =>
+            {
+                let AvianPickupActorState::Holding(prop) = state else {
+                    unreachable!()
+                };
+                Some(Verb::ChargeThrow(prop))
+            }
+            AvianPickupAction::ThrowAt(target)
+                if cooldown.finished(AvianPickupAction::Throw)
+                    && matches!(state, AvianPickupActorState::Holding(..)) =>
+            {
+                let AvianPickupActorState::Holding(prop) = state else {
+                    unreachable!()
+                };
+                Some(Verb::Throw {
+                    prop,
+                    target: Some(target),
+                })
+            }
+            // The cooldown was already checked when we started charging.
+            AvianPickupAction::ReleaseThrow => charging
+                .map(|charging| charging.prop)
+                .filter(|&prop| held_props.contains(prop))
+                .map(|prop| Verb::Throw { prop, target: None }),
+            AvianPickupAction::Punt
+                if cooldown.finished(AvianPickupAction::Punt)
+                    && !matches!(state, AvianPickupActorState::Holding(..)) =>
+            {
+                Some(Verb::Punt)
+            }
             AvianPickupAction::Pull
-                if matches!(
-                    state,
-                    AvianPickupActorState::Idle | AvianPickupActorState::Pulling(..)
-                ) && cooldown.finished(AvianPickupAction::Pull) =>
+                if can_hold_more && cooldown.finished(AvianPickupAction::Pull) =>
             {
                 Some(Verb::Pull)
             }
@@ -1,6 +1,10 @@
-use avian3d::math::{Scalar, TAU};
+use avian::math::{Scalar, TAU};
 
-use crate::{prelude::*, verb::Dropping};
+use crate::{
+    math::{clamp_angular_speed, GetRigidBodyVelocity as _, ToVector as _},
+    prelude::*,
+    verb::{Dropping, PendingDrop},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(PhysicsSchedule, drop.in_set(HandleVerbSystem::Drop));
@@ -9,32 +13,47 @@ pub(super) fn plugin(app: &mut App) {
 /// DetachObject
 fn drop(
     mut commands: Commands,
-    mut q_actor: Query<(Entity, &mut AvianPickupActorState, &mut Cooldown, &Dropping)>,
-    mut q_prop: Query<(&mut LinearVelocity, &mut AngularVelocity)>,
+    mut q_actor: Query<(Entity, &AvianPickupActor, &mut Cooldown, &Dropping)>,
+    // The actor might be moving, so we need to read its velocity
+    // while also writing the prop's velocity.
+    mut q_prop_and_actor: ParamSet<(
+        Query<(&RigidBody, &mut LinearVelocity, &mut AngularVelocity)>,
+        Query<(Option<&LinearVelocity>, Option<&Parent>)>,
+    )>,
     mut w_drop_event: EventWriter<PropDropped>,
 ) {
-    for (actor, mut state, mut cooldown, drop) in q_actor.iter_mut() {
-        let prop = drop.prop;
-        *state = AvianPickupActorState::Idle;
-        cooldown.drop();
+    for (actor, config, mut cooldown, dropping) in q_actor.iter_mut() {
         commands.entity(actor).remove::<Dropping>();
-        w_drop_event.send(PropDropped {
-            actor,
-            prop,
-            forced: drop.forced,
-        });
-        // Safety: the prop is a dynamic rigid body and thus is guaranteed to have a
-        // linvel and angvel.
-        let Ok((mut velocity, mut angvel)) = q_prop.get_mut(prop) else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
-            continue;
-        };
-        // HL2 uses 190 inches per second, which is 4.826 meters per second.
-        // let's round that to 5 m/s.
-        const HL2_NORM_SPEED: Scalar = 5.0;
-        const MAX_DROP_LINEAR_SPEED: Scalar = HL2_NORM_SPEED * 1.5;
-        const MAX_DROP_ANGULAR_SPEED: Scalar = TAU * 2.0;
-        velocity.0 = velocity.clamp_length_max(MAX_DROP_LINEAR_SPEED);
-        angvel.0 = angvel.clamp_length_max(MAX_DROP_ANGULAR_SPEED);
+        let inherited_velocity =
+            q_prop_and_actor.p1().get_rigid_body_velocity(actor) * config.velocity_inheritance;
+        for &PendingDrop { prop, forced } in dropping.0.iter() {
+            cooldown.drop(&config.cooldown);
+            w_drop_event.send(PropDropped {
+                actor,
+                prop,
+                forced,
+            });
+            let mut q_prop = q_prop_and_actor.p0();
+            // Safety: the prop is a rigid body and thus is guaranteed to have a
+            // linvel and angvel.
+            let Ok((rigid_body, mut velocity, mut angvel)) = q_prop.get_mut(prop) else {
+                error!("Prop entity was deleted or in an invalid state. Ignoring.");
+                continue;
+            };
+            if *rigid_body != RigidBody::Dynamic {
+                // The prop went back to being kinematic or static,
+                // see `DynamicWhileHeld`.
+                continue;
+            }
+            // HL2 uses 190 inches per second, which is 4.826 meters per second.
+            // let's round that to 5 m/s.
+            const HL2_NORM_SPEED: Scalar = 5.0;
+            const MAX_DROP_LINEAR_SPEED: Scalar = HL2_NORM_SPEED * 1.5;
+            const MAX_DROP_ANGULAR_SPEED: Scalar = TAU * 2.0;
+            // Clamp first so that the actor's own motion is not limited.
+            velocity.0 =
+                velocity.clamp_length_max(MAX_DROP_LINEAR_SPEED) + inherited_velocity.to_vector();
+            angvel.0 = clamp_angular_speed(angvel.0, MAX_DROP_ANGULAR_SPEED);
+        }
     }
 }
@@ -0,0 +1,56 @@
+use bevy::ecs::{
+    archetype::Archetypes, component::Components, entity::Entities, system::SystemParam,
+};
+
+use crate::prelude::*;
+
+/// Applies an [`AvianPickupRigidBodyFilter`] to rigid bodies and the colliders
+/// belonging to them.
+#[derive(SystemParam)]
+pub(crate) struct RigidBodyFilterParams<'w, 's> {
+    components: &'w Components,
+    entities: &'w Entities,
+    archetypes: &'w Archetypes,
+    q_collider_parent: Query<'w, 's, &'static ColliderParent>,
+    q_mass: Query<'w, 's, (&'static Mass, Option<&'static NonPickupMass>)>,
+}
+
+impl RigidBodyFilterParams<'_, '_> {
+    /// Whether the rigid body passes the filter.
+    pub(crate) fn allows(&self, filter: &AvianPickupRigidBodyFilter, rigid_body: Entity) -> bool {
+        if filter.excluded_entities.contains(&rigid_body) {
+            return false;
+        }
+        // Held props are lighter than usual, so use their real mass.
+        if let Ok((mass, non_pickup_mass)) = self.q_mass.get(rigid_body) {
+            let mass = non_pickup_mass.map_or(mass.0, |mass| mass.0);
+            if !filter.mass_range.contains(&mass) {
+                return false;
+            }
+        }
+        if filter.with.is_empty() && filter.without.is_empty() {
+            return true;
+        }
+        let Some(location) = self.entities.get(rigid_body) else {
+            return false;
+        };
+        let archetype = &self.archetypes[location.archetype_id];
+        let has_component = |type_id: &_| {
+            self.components
+                .get_id(*type_id)
+                .is_some_and(|id| archetype.contains(id))
+        };
+        filter.with.iter().all(has_component) && !filter.without.iter().any(has_component)
+    }
+
+    /// Whether the rigid body the collider belongs to passes the filter.
+    pub(crate) fn allows_collider(
+        &self,
+        filter: &AvianPickupRigidBodyFilter,
+        collider: Entity,
+    ) -> bool {
+        self.q_collider_parent
+            .get(collider)
+            .is_ok_and(|rigid_body| self.allows(filter, rigid_body.get()))
+    }
+}
@@ -0,0 +1,176 @@
+use super::prelude::{HoldAnchor, HoldError, HoldForces, Holder, Holders, ShadowParams};
+use crate::{
+    math::{GetBestGlobalTransform, ToQuat as _, ToVec3 as _},
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(attach);
+}
+
+/// Triggered on an actor to make it start holding the given prop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub(crate) struct AttachProp(pub(crate) Entity);
+
+/// CGrabController::AttachEntity
+fn attach(
+    trigger: Trigger<AttachProp>,
+    mut commands: Commands,
+    mut q_actor: Query<(
+        &AvianPickupActor,
+        &mut AvianPickupActorState,
+        &mut AvianPickupActorHeldProps,
+    )>,
+    q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
+    mut q_prop: Query<(
+        &Position,
+        &Rotation,
+        &mut Mass,
+        &mut RigidBody,
+        Option<&PickupMassOverride>,
+        Option<&HoldErrorWarmupOverride>,
+        Option<&mut NonPickupMass>,
+        Option<&mut NonPickupRigidBody>,
+        Option<&mut Holders>,
+    )>,
+    mut w_joined_event: EventWriter<CoHolderJoined>,
+) {
+    let actor = trigger.entity();
+    let prop = trigger.event().0;
+    let Ok((config, mut state, mut held_props)) = q_actor.get_mut(actor) else {
+        error!("Actor entity was deleted or in an invalid state. Ignoring.");
+        return;
+    };
+    if held_props.contains(prop) {
+        return;
+    }
+    let actor_transform = q_actor_transform.get_best_global_transform(actor);
+    let Ok((
+        position,
+        rotation,
+        mut mass,
+        mut rigid_body,
+        pickup_mass,
+        error_warmup,
+        non_pickup_mass,
+        non_pickup_rigid_body,
+        holders,
+    )) = q_prop.get_mut(prop)
+    else {
+        error!("Prop entity was deleted or in an invalid state. Ignoring.");
+        return;
+    };
+    held_props.0.push(prop);
+    *state = AvianPickupActorState::Holding(prop);
+
+    let holder = Holder {
+        actor,
+        pre_pickup_rotation: prop_rotation_to_actor_space(rotation.to_quat(), actor_transform),
+        target_position: position.0.to_vec3(),
+        target_rotation: rotation.to_quat(),
+    };
+    if let Some(mut holders) = holders {
+        // Someone else is already holding this prop, so the mass has already
+        // been taken care of.
+        holders.holders.push(holder);
+        w_joined_event.send(CoHolderJoined { actor, prop });
+        return;
+    }
+
+    let new_mass = if config.hold.mode.respects_mass() {
+        // No need to make the prop lighter, as it is not forced to the target.
+        mass.0
+    } else {
+        pickup_mass
+            .map(|m| m.0)
+            .unwrap_or(config.hold.temporary_prop_mass)
+    };
+    if let Some(mut non_pickup_mass) = non_pickup_mass {
+        non_pickup_mass.0 = mass.0;
+    } else {
+        // This has some overhead, even if it only overwrites the existing component,
+        // so let's try to avoid it if possible
+        commands.entity(prop).insert(NonPickupMass(mass.0));
+    }
+    if let Some(mut non_pickup_rigid_body) = non_pickup_rigid_body {
+        non_pickup_rigid_body.0 = *rigid_body;
+    } else {
+        commands
+            .entity(prop)
+            .insert(NonPickupRigidBody(*rigid_body));
+    }
+    // Only props with `DynamicWhileHeld` can be non-dynamic at this point.
+    if *rigid_body != RigidBody::Dynamic {
+        *rigid_body = RigidBody::Dynamic;
+    }
+    // Only props with `CooperativeHold` can be too heavy at this point.
+    // They will be lifted once enough actors join in.
+    let lifted = mass.0 < config.pull.max_prop_mass;
+    commands.entity(prop).insert((
+        HeldProp,
+        ShadowParams::default(),
+        HoldError::new(
+            error_warmup
+                .map(|w| w.0)
+                .unwrap_or(config.hold.error_warmup),
+        ),
+        Holders {
+            holders: vec![holder],
+            pickup_mass: new_mass,
+            lifted,
+        },
+    ));
+
+    if let AvianPickupHoldMode::Joint {
+        compliance,
+        linear_damping,
+        angular_damping,
+    } = config.hold.mode
+    {
+        let anchor = commands
+            .spawn((
+                Name::new("Hold Anchor"),
+                RigidBody::Kinematic,
+                *position,
+                *rotation,
+                TransformBundle::from_transform(
+                    Transform::from_translation(position.0.to_vec3())
+                        .with_rotation(rotation.to_quat()),
+                ),
+            ))
+            .id();
+        let joint = commands
+            .spawn((
+                Name::new("Hold Joint"),
+                FixedJoint::new(anchor, prop)
+                    .with_compliance(compliance)
+                    .with_linear_velocity_damping(linear_damping)
+                    .with_angular_velocity_damping(angular_damping),
+            ))
+            .id();
+        commands.entity(prop).insert(HoldAnchor { anchor, joint });
+    } else if let AvianPickupHoldMode::PdController { .. } = config.hold.mode {
+        commands.entity(prop).insert(HoldForces);
+    }
+
+    if lifted {
+        mass.0 = new_mass;
+    }
+    // The original code also does some damping stuff, but then deactivates
+    // drag? Seems like a no-op to me
+
+    // The original code now does some stuff with `AlignAngles`, but it only
+    // does so when `m_angleAlignment != 0`, which does not seem to be the
+    // case for HL2 deathmatch, judging by the code? Anyhoot, per
+    // discussions on Discord, that code seems to align the prop to
+    // the coordinate axes if it is closer than 30 degrees to them.
+    // Does not seem to be that useful.
+}
+
+/// TransformAnglesToPlayerSpace
+fn prop_rotation_to_actor_space(rot: Quat, actor: Transform) -> Quat {
+    let world_to_actor = actor.compute_affine().inverse();
+    let rot_to_world = Transform::from_rotation(rot).compute_affine();
+    let local_affine = world_to_actor * rot_to_world;
+    Quat::from_affine3(&local_affine)
+}
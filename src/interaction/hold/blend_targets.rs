@@ -0,0 +1,96 @@
+use avian::math::Scalar;
+
+use super::{prelude::*, HoldSystem};
+use crate::{
+    prelude::*,
+    verb::{SetVerb, Verb},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        PhysicsSchedule,
+        blend_targets.in_set(HoldSystem::BlendTargets),
+    );
+}
+
+/// Combines the targets of all actors holding a prop into a single target.
+/// Each actor is weighted by its
+/// [`AvianPickupActorPullConfig::max_prop_mass`], which also determines whether
+/// the actors are strong enough to lift the prop together.
+/// If they no longer are, e.g. because a co-holder let go, the remaining
+/// actors drop the prop.
+fn blend_targets(
+    mut commands: Commands,
+    mut q_prop: Query<(
+        Entity,
+        &mut Holders,
+        &mut ShadowParams,
+        &mut HoldError,
+        &mut Mass,
+        &NonPickupMass,
+        Option<&HoldErrorWarmupOverride>,
+    )>,
+    q_actor: Query<&AvianPickupActor>,
+) {
+    for (prop, mut holders, mut shadow, mut hold_error, mut mass, non_pickup_mass, error_warmup) in
+        q_prop.iter_mut()
+    {
+        let Some(first) = holders.holders.first().copied() else {
+            continue;
+        };
+        let mut strength = 0.0;
+        let mut total_weight = 0.0;
+        let mut position = Vec3::ZERO;
+        let mut rotation = Vec4::ZERO;
+        for holder in holders.holders.iter() {
+            let Ok(config) = q_actor.get(holder.actor) else {
+                error!("Actor entity was deleted or in an invalid state. Ignoring.");
+                continue;
+            };
+            strength += config.pull.max_prop_mass;
+            let weight = config.pull.max_prop_mass.max(Scalar::EPSILON);
+            total_weight += weight;
+            position += holder.target_position * weight;
+            // Quaternions `q` and `-q` represent the same rotation, so make sure
+            // we are not averaging opposite ones.
+            let target_rotation = if first.target_rotation.dot(holder.target_rotation) < 0.0 {
+                -holder.target_rotation
+            } else {
+                holder.target_rotation
+            };
+            rotation += Vec4::from(target_rotation) * weight;
+        }
+        if total_weight > 0.0 {
+            shadow.target_position = position / total_weight;
+            shadow.target_rotation = Quat::from_vec4(rotation).normalize();
+        }
+
+        let lifted = non_pickup_mass.0 < strength;
+        if holders.lifted && !lifted {
+            // Waiting for help is only fine before the prop was lifted.
+            // Otherwise, the remaining actors would be stuck holding a prop
+            // that never moves and thus never strains the hold enough to be dropped.
+            for holder in holders.holders.iter() {
+                commands
+                    .entity(holder.actor)
+                    .add(SetVerb::new(Verb::Drop { prop, forced: true }));
+            }
+        }
+        if lifted != holders.lifted {
+            holders.lifted = lifted;
+            mass.0 = if lifted {
+                holders.pickup_mass
+            } else {
+                non_pickup_mass.0
+            };
+            // The prop needs some time to reach its target again.
+            let warmup = error_warmup.map(|w| w.0).or_else(|| {
+                q_actor
+                    .get(first.actor)
+                    .ok()
+                    .map(|config| config.hold.error_warmup)
+            });
+            *hold_error = warmup.map(HoldError::new).unwrap_or_default();
+        }
+    }
+}
@@ -1,10 +1,24 @@
-use avian3d::math::{Scalar, TAU};
+use avian::math::{Scalar, TAU};
 use bevy::prelude::*;
 
-pub(super) fn plugin(_app: &mut App) {}
+use crate::prelude::AvianPickupActor;
 
-#[derive(Debug, Copy, Clone, Component)]
-pub(crate) struct ShadowParams {
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(ShadowParams, HoldError)>();
+}
+
+/// The target a held prop is steered towards.
+/// When multiple actors hold the prop, this is the blend of all their targets.\
+/// This component is automatically added to a prop for as long as it is held,
+/// do not add or remove it. It is only meant to be read.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ShadowParams {
     /// Global target position of the held prop
     pub(crate) target_position: Vec3,
     /// Global target rotation of the held prop
@@ -24,28 +38,129 @@ impl Default for ShadowParams {
     }
 }
 
+impl ShadowParams {
+    /// Global target position of the held prop
+    pub fn target_position(&self) -> Vec3 {
+        self.target_position
+    }
+
+    /// Global target rotation of the held prop
+    pub fn target_rotation(&self) -> Quat {
+        self.target_rotation
+    }
+
+    /// The maximum angular speed in rad/s the prop is steered with
+    pub fn max_angular(&self) -> Scalar {
+        self.max_angular
+    }
+
+    /// The maximum linear speed in m/s the prop is steered with
+    pub fn max_speed(&self) -> Scalar {
+        self.max_speed
+    }
+}
+
 /// Cache for accumulating errors when holding an object.
-/// When this reaches a critical value, the object will be dropped.
-#[derive(Debug, Copy, Clone, Component)]
-pub(crate) struct HoldError {
+/// When this reaches a critical value, the object will be dropped.\
+/// This component is automatically added to a prop for as long as it is held,
+/// do not add or remove it. It is only meant to be read, e.g. to show a grip
+/// meter through [`HoldError::strain`].
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct HoldError {
     /// Time until error starts accumulating
     pub(crate) error_time: f32,
     /// The distance between the object and the target position
     pub(crate) error: f32,
+    /// The error relative to the error at which the prop is dropped
+    pub(crate) strain: f32,
+    /// Whether a [`HoldStrained`](crate::output::HoldStrained) was sent
+    /// and the strain has not gone back below the threshold since.
+    pub(crate) strained: bool,
 }
 
 impl HoldError {
-    pub(crate) fn reset(&mut self) {
-        *self = Self::default();
+    /// Creates a new error that only starts accumulating after `warmup`
+    /// seconds.
+    pub(crate) fn new(warmup: f32) -> Self {
+        Self {
+            error_time: -warmup,
+            error: 0.0,
+            strain: 0.0,
+            strained: false,
+        }
+    }
+
+    /// The smoothed distance in meters between the prop and its target.
+    pub fn error(&self) -> f32 {
+        self.error
+    }
+
+    /// How strained the hold is, from 0.0 for a prop that is right on target
+    /// to 1.0 for a prop that is about to be forcefully dropped.
+    pub fn strain(&self) -> f32 {
+        self.strain
     }
 }
 
 impl Default for HoldError {
     fn default() -> Self {
-        Self {
-            // 1 second until error starts accumulating
-            error_time: -1.0,
-            error: 0.0,
-        }
+        Self::new(AvianPickupActor::default().hold.error_warmup)
+    }
+}
+
+/// The actors holding a prop. Usually, this is just a single actor, but props
+/// with [`CooperativeHold`](crate::prop::CooperativeHold) can be held by
+/// multiple actors at once.
+/// Lives on the prop for as long as it is held.
+#[derive(Debug, Clone, Component)]
+pub(crate) struct Holders {
+    /// In the order the actors started holding the prop.
+    pub(crate) holders: Vec<Holder>,
+    /// The mass of the prop while it is lifted.
+    pub(crate) pickup_mass: Scalar,
+    /// Whether the holders are strong enough to lift the prop together.
+    /// Props that are not lifted are not steered towards their target.
+    pub(crate) lifted: bool,
+}
+
+impl Holders {
+    pub(crate) fn get_mut(&mut self, actor: Entity) -> Option<&mut Holder> {
+        self.holders.iter_mut().find(|holder| holder.actor == actor)
     }
 }
+
+/// The kinematic anchor a prop is attached to when held with
+/// [`AvianPickupHoldMode::Joint`](crate::prelude::AvianPickupHoldMode::Joint).
+/// Lives on the prop for as long as it is held.
+#[derive(Debug, Copy, Clone, Component)]
+pub(crate) struct HoldAnchor {
+    /// The kinematic rigid body following the prop's target
+    pub(crate) anchor: Entity,
+    /// The joint connecting the anchor to the prop
+    pub(crate) joint: Entity,
+}
+
+/// Marks a prop that is held with
+/// [`AvianPickupHoldMode::PdController`](crate::prelude::AvianPickupHoldMode::PdController),
+/// so that its forces are cleared again when it is released.
+/// Lives on the prop for as long as it is held.
+#[derive(Debug, Copy, Clone, Component)]
+pub(crate) struct HoldForces;
+
+/// A single actor holding a prop.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Holder {
+    pub(crate) actor: Entity,
+    /// The prop's rotation in the actor's space at the time it was picked up.
+    pub(crate) pre_pickup_rotation: Quat,
+    /// Global target position of the held prop according to this actor
+    pub(crate) target_position: Vec3,
+    /// Global target rotation of the held prop according to this actor
+    pub(crate) target_rotation: Quat,
+}
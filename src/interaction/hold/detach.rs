@@ -0,0 +1,115 @@
+use super::prelude::{HoldAnchor, HoldError, HoldForces, Holders, ShadowParams};
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(detach);
+}
+
+/// Triggered on an actor to make it stop holding the given prop.
+/// The prop's velocity is left untouched, as that is handled by whoever
+/// released the prop, e.g. a throw or a drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub(crate) struct DetachProp(pub(crate) Entity);
+
+fn detach(
+    trigger: Trigger<DetachProp>,
+    mut commands: Commands,
+    mut q_actor: Query<(&mut AvianPickupActorState, &mut AvianPickupActorHeldProps)>,
+    mut q_prop: Query<(
+        &mut Mass,
+        &mut RigidBody,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+        &mut ExternalForce,
+        &mut ExternalTorque,
+        Option<&NonPickupMass>,
+        Option<&NonPickupRigidBody>,
+        Option<&mut Holders>,
+        Option<&HoldAnchor>,
+        Has<HoldForces>,
+        Has<HeldProp>,
+    )>,
+    mut w_left_event: EventWriter<CoHolderLeft>,
+) {
+    let actor = trigger.entity();
+    let prop = trigger.event().0;
+    let Ok((mut state, mut held_props)) = q_actor.get_mut(actor) else {
+        error!("Actor entity was deleted or in an invalid state. Ignoring.");
+        return;
+    };
+    let Some(index) = held_props.0.iter().position(|&held| held == prop) else {
+        // Already detached, e.g. because the prop was first thrown and then
+        // dropped in the same update.
+        return;
+    };
+    held_props.0.remove(index);
+    *state = match held_props.last() {
+        Some(prop) => AvianPickupActorState::Holding(prop),
+        None => AvianPickupActorState::Idle,
+    };
+
+    let Ok((
+        mut mass,
+        mut rigid_body,
+        mut velocity,
+        mut angvel,
+        mut force,
+        mut torque,
+        non_pickup_mass,
+        non_pickup_rigid_body,
+        holders,
+        hold_anchor,
+        has_hold_forces,
+        has_held_marker,
+    )) = q_prop.get_mut(prop)
+    else {
+        error!("Prop entity was deleted or in an invalid state. Ignoring.");
+        return;
+    };
+    if !has_held_marker {
+        error!(
+            "A held prop that is no longer being held was not actually marked as held. This is supremely weird. Ignoring."
+        );
+        return;
+    }
+    if let Some(mut holders) = holders {
+        holders.holders.retain(|holder| holder.actor != actor);
+        if !holders.holders.is_empty() {
+            // The others keep holding the prop.
+            w_left_event.send(CoHolderLeft { actor, prop });
+            return;
+        }
+    }
+    commands.entity(prop).remove::<(
+        HeldProp,
+        ShadowParams,
+        HoldError,
+        Holders,
+        HoldAnchor,
+        HoldForces,
+    )>();
+    if has_hold_forces {
+        force.clear();
+        torque.clear();
+    }
+    if let Some(hold_anchor) = hold_anchor {
+        commands.entity(hold_anchor.joint).despawn();
+        commands.entity(hold_anchor.anchor).despawn();
+    }
+    if let Some(non_pickup_rigid_body) = non_pickup_rigid_body {
+        if *rigid_body != non_pickup_rigid_body.0 {
+            *rigid_body = non_pickup_rigid_body.0;
+            // Otherwise a kinematic prop would keep drifting with the
+            // velocity it had while being held.
+            *velocity = LinearVelocity::ZERO;
+            *angvel = AngularVelocity::ZERO;
+        }
+    }
+    let Some(non_pickup_mass) = non_pickup_mass else {
+        error!(
+            "A held prop that is no longer being held failed to get its pre-pickup mass back. Ignoring."
+        );
+        return;
+    };
+    mass.0 = non_pickup_mass.0;
+}
@@ -0,0 +1,38 @@
+use super::{
+    prelude::{HoldAnchor, Holders, ShadowParams},
+    HoldSystem,
+};
+use crate::{
+    math::{to_rotation, ToVector as _},
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        PhysicsSchedule,
+        move_anchors.in_set(HoldSystem::SetVelocities),
+    );
+}
+
+/// Moves the kinematic anchors of props held with
+/// [`AvianPickupHoldMode::Joint`] to their targets.
+/// The joints then drag the props along.
+fn move_anchors(
+    q_prop: Query<(&Holders, &ShadowParams, &HoldAnchor, &Position, &Rotation)>,
+    mut q_anchor: Query<(&mut Position, &mut Rotation), Without<Holders>>,
+) {
+    for (holders, shadow, hold_anchor, prop_position, prop_rotation) in q_prop.iter() {
+        let Ok((mut position, mut rotation)) = q_anchor.get_mut(hold_anchor.anchor) else {
+            error!("Hold anchor entity was deleted or in an invalid state. Ignoring.");
+            continue;
+        };
+        if holders.lifted {
+            position.0 = shadow.target_position.to_vector();
+            *rotation = to_rotation(shadow.target_rotation);
+        } else {
+            // Keep the anchor on the prop so that the joint does not try to lift it.
+            *position = *prop_position;
+            *rotation = *prop_rotation;
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use crate::prelude::*;
 
+mod attach;
+mod blend_targets;
 mod components;
-mod on_add_holding;
-mod on_remove_holding;
+mod detach;
+mod joint;
+mod pd_controller;
 mod set_velocities;
 mod update_error;
 mod update_targets;
@@ -12,18 +15,22 @@ pub(super) fn plugin(app: &mut App) {
         (
             HoldSystem::UpdateError,
             HoldSystem::SetTargets,
+            HoldSystem::BlendTargets,
             HoldSystem::SetVelocities,
         )
             .chain()
             .in_set(HandleVerbSystem::Hold),
     )
     .add_plugins((
-        on_add_holding::plugin,
-        on_remove_holding::plugin,
+        attach::plugin,
+        detach::plugin,
         components::plugin,
         update_error::plugin,
         update_targets::plugin,
+        blend_targets::plugin,
         set_velocities::plugin,
+        joint::plugin,
+        pd_controller::plugin,
     ));
 }
 
@@ -31,9 +38,15 @@ pub(super) fn plugin(app: &mut App) {
 enum HoldSystem {
     UpdateError,
     SetTargets,
+    BlendTargets,
     SetVelocities,
 }
 
 pub(super) mod prelude {
-    pub(crate) use super::components::{HoldError, ShadowParams};
+    pub use super::components::{HoldError, ShadowParams};
+    pub(crate) use super::{
+        attach::AttachProp,
+        components::{HoldAnchor, HoldForces, Holder, Holders},
+        detach::DetachProp,
+    };
 }
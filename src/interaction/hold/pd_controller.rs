@@ -0,0 +1,166 @@
+use std::f32::consts::{PI, TAU};
+
+use avian::math::Scalar;
+
+use super::{
+    prelude::{HoldForces, Holders, ShadowParams},
+    HoldSystem,
+};
+use crate::{
+    math::{from_angular_value, to_angular_value, ToQuat as _, ToVec3 as _, ToVector as _},
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        PhysicsSchedule,
+        apply_forces.in_set(HoldSystem::SetVelocities),
+    );
+}
+
+/// Pushes props held with [`AvianPickupHoldMode::PdController`] towards their
+/// targets.
+fn apply_forces(
+    mut q_prop: Query<
+        (
+            &Holders,
+            &ShadowParams,
+            &mut ExternalForce,
+            &mut ExternalTorque,
+            &LinearVelocity,
+            &AngularVelocity,
+            &Position,
+            &Rotation,
+            &Mass,
+            Option<&GravityScale>,
+        ),
+        With<HoldForces>,
+    >,
+    q_actor: Query<&AvianPickupActor>,
+    gravity: Res<Gravity>,
+) {
+    for (
+        holders,
+        shadow,
+        mut force,
+        mut torque,
+        velocity,
+        angvel,
+        position,
+        rotation,
+        mass,
+        gravity_scale,
+    ) in q_prop.iter_mut()
+    {
+        if !holders.lifted {
+            force.clear();
+            torque.clear();
+            continue;
+        }
+        // When multiple actors hold the prop, the one that picked it up first
+        // determines how it moves.
+        let Some(actor) = holders
+            .holders
+            .first()
+            .and_then(|holder| q_actor.get(holder.actor).ok())
+        else {
+            error!("Actor entity was deleted or in an invalid state. Ignoring.");
+            continue;
+        };
+        let AvianPickupHoldMode::PdController {
+            stiffness,
+            damping,
+            max_force,
+            angular_stiffness,
+            angular_damping,
+            max_torque,
+        } = actor.hold.mode
+        else {
+            // The actor switched modes while holding the prop.
+            // The new mode only applies to props picked up from now on.
+            continue;
+        };
+
+        let gravity_scale = gravity_scale.map_or(1.0, |scale| scale.0);
+        let linear = linear_force(
+            shadow.target_position - position.0.to_vec3(),
+            velocity.0.to_vec3(),
+            gravity.0.to_vec3() * gravity_scale,
+            mass.0,
+            stiffness,
+            damping,
+            max_force,
+        );
+        force.set_force(linear.to_vector());
+
+        let delta_rotation = shadow.target_rotation * rotation.to_quat().inverse();
+        let (axis, angle) = delta_rotation.to_axis_angle();
+        // This is needed because otherwise we will sometimes rotate the long way around
+        let angle = if angle > PI { angle - TAU } else { angle };
+        let angular =
+            axis * angle * angular_stiffness - from_angular_value(angvel.0) * angular_damping;
+        torque.set_torque(to_angular_value(angular.clamp_length_max(max_torque)));
+    }
+}
+
+/// The force pushing the prop towards its target. Includes a feedforward term
+/// cancelling out gravity, as otherwise heavy props would sag below their
+/// target by `mass * gravity / stiffness`.
+fn linear_force(
+    delta_position: Vec3,
+    velocity: Vec3,
+    gravity: Vec3,
+    mass: Scalar,
+    stiffness: Scalar,
+    damping: Scalar,
+    max_force: Scalar,
+) -> Vec3 {
+    let linear = delta_position * stiffness - velocity * damping - gravity * mass;
+    linear.clamp_length_max(max_force)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn heaviest_prop_settles_on_target() {
+        let config = AvianPickupActor::default();
+        let AvianPickupHoldMode::PdController {
+            stiffness,
+            damping,
+            max_force,
+            ..
+        } = AvianPickupHoldMode::pd_controller()
+        else {
+            unreachable!()
+        };
+        let mass = config.pull.max_prop_mass;
+        let gravity = Vec3::NEG_Y * 9.81;
+        let target = Vec3::ZERO;
+        // Start just within reach of a forced drop.
+        let offset = config.hold.max_error * 0.9;
+        let mut position = target + Vec3::X * offset;
+        let mut velocity = Vec3::ZERO;
+        let mut overshoot = 0.0_f32;
+        let dt = 1.0 / 64.0;
+        for _ in 0..(64 * 5) {
+            let force = linear_force(
+                target - position,
+                velocity,
+                gravity,
+                mass,
+                stiffness,
+                damping,
+                max_force,
+            );
+            velocity += (force / mass + gravity) * dt;
+            position += velocity * dt;
+            overshoot = overshoot.max(target.x - position.x);
+            // Gravity is cancelled out, so the prop does not sag.
+            assert!(position.y.abs() < 1e-3);
+        }
+        assert!(overshoot < offset * 0.5);
+        assert!(position.distance(target) < 1e-2);
+    }
+}
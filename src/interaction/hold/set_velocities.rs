@@ -1,7 +1,13 @@
 use std::f32::consts::{PI, TAU};
 
-use super::{prelude::ShadowParams, HoldSystem};
-use crate::{prelude::*, verb::Holding};
+use super::{
+    prelude::{HoldAnchor, HoldForces, Holders, ShadowParams},
+    HoldSystem,
+};
+use crate::{
+    math::{to_angular_value, ToQuat as _, ToVec3 as _, ToVector as _},
+    prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -13,13 +19,18 @@ pub(super) fn plugin(app: &mut App) {
 /// CGrabController::Simulate
 fn set_velocities(
     time: Res<Time>,
-    mut q_prop: Query<(
-        &mut LinearVelocity,
-        &mut AngularVelocity,
-        &Position,
-        &Rotation,
-    )>,
-    mut q_actor: Query<(&ShadowParams, &Holding, &AvianPickupActor)>,
+    mut q_prop: Query<
+        (
+            &Holders,
+            &ShadowParams,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            &Position,
+            &Rotation,
+        ),
+        (Without<HoldAnchor>, Without<HoldForces>),
+    >,
+    q_actor: Query<&AvianPickupActor>,
 ) {
     // Valve uses CGrabController::Simulate, which does *a lot* of stuff,
     // but from testing, it seems like this does the job pretty much identically,
@@ -27,16 +38,24 @@ fn set_velocities(
     // check out the commit aa51b2bc4dbc52049476135ba146b3ba143b681a
     let dt = time.delta_seconds();
     let inv_dt = dt.recip();
-    for (shadow, holding, actor) in q_actor.iter_mut() {
-        let prop = holding.0;
-        let Ok((mut velocity, mut angvel, position, rotation)) = q_prop.get_mut(prop) else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+    for (holders, shadow, mut velocity, mut angvel, position, rotation) in q_prop.iter_mut() {
+        if !holders.lifted {
+            continue;
+        }
+        // When multiple actors hold the prop, the one that picked it up first
+        // determines how it moves.
+        let Some(actor) = holders
+            .holders
+            .first()
+            .and_then(|holder| q_actor.get(holder.actor).ok())
+        else {
+            error!("Actor entity was deleted or in an invalid state. Ignoring.");
             continue;
         };
 
-        let delta_position = shadow.target_position - position.0;
+        let delta_position = shadow.target_position - position.0.to_vec3();
 
-        let delta_rotation = shadow.target_rotation * rotation.0.inverse();
+        let delta_rotation = shadow.target_rotation * rotation.to_quat().inverse();
         let (axis, angle) = delta_rotation.to_axis_angle();
         // This is needed because otherwise we will sometimes rotate the long way around
         let angle = if angle > PI { angle - TAU } else { angle };
@@ -46,13 +65,13 @@ fn set_velocities(
         // things like overshooting as we are in a fixed timestep.
         // Negative because the dt is already inverted
         let vel_ease = f32::exp(-actor.hold.linear_velocity_easing);
-        velocity.0 = (delta_position * inv_dt * vel_ease).clamp_length_max(shadow.max_speed);
-        velocity.0 = zero_if_near_zero(velocity.0);
+        let linear = (delta_position * inv_dt * vel_ease).clamp_length_max(shadow.max_speed);
+        velocity.0 = zero_if_near_zero(linear).to_vector();
 
         let angvel_ease = f32::exp(-actor.hold.angular_velocity_easing);
-        angvel.0 = (delta_rotation_scaled_axis * inv_dt * angvel_ease)
+        let angular = (delta_rotation_scaled_axis * inv_dt * angvel_ease)
             .clamp_length_max(shadow.max_angular);
-        angvel.0 = zero_if_near_zero(angvel.0);
+        angvel.0 = to_angular_value(zero_if_near_zero(angular));
     }
 }
 
@@ -1,5 +1,5 @@
 use super::{prelude::*, HoldSystem};
-use crate::{prelude::*, verb::Holding};
+use crate::{math::ToVec3 as _, prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -11,31 +11,127 @@ pub(super) fn plugin(app: &mut App) {
 /// CGrabController::ComputeError(),
 pub fn update_error(
     time: Res<Time>,
-    q_prop: Query<&Position>,
-    mut q_actor: Query<(&mut HoldError, &ShadowParams, &Holding)>,
+    mut q_prop: Query<(
+        Entity,
+        &mut HoldError,
+        &ShadowParams,
+        &Holders,
+        &Position,
+        Option<&MaxHoldErrorOverride>,
+        Option<&MaxHoldErrorTimeOverride>,
+        Option<&HoldStrainedFractionOverride>,
+    )>,
+    q_actor: Query<&AvianPickupActor>,
+    mut w_strained_event: EventWriter<HoldStrained>,
 ) {
     let dt = time.delta_seconds();
-    for (mut hold_error, shadow, holding) in q_actor.iter_mut() {
-        let prop = holding.0;
-        hold_error.error_time += dt;
-        if hold_error.error_time <= 0.0 {
+    for (
+        prop,
+        mut hold_error,
+        shadow,
+        holders,
+        position,
+        max_error,
+        max_error_time,
+        strained_fraction,
+    ) in q_prop.iter_mut()
+    {
+        if !holders.lifted {
             continue;
         }
-        let Ok(position) = q_prop.get(prop) else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+        // When multiple actors hold the prop, the one that picked it up first
+        // determines how strained the hold is.
+        let Some(config) = holders
+            .holders
+            .first()
+            .and_then(|holder| q_actor.get(holder.actor).ok())
+        else {
+            error!("Actor entity was deleted or in an invalid state. Ignoring.");
             continue;
         };
-        let mut error = (position.0 - shadow.target_position).length();
-        if hold_error.error_time > 1.0 {
-            hold_error.error_time = 1.0;
+        hold_error.error_time += dt;
+        if hold_error.error_time <= 0.0 {
+            continue;
+        }
+        let mut error = (position.0.to_vec3() - shadow.target_position).length();
+        // Used as a lerp factor below, so anything above 1.0 would overshoot.
+        let max_error_time = max_error_time
+            .map(|t| t.0)
+            .unwrap_or(config.hold.max_error_time)
+            .clamp(0.0, 1.0);
+        if hold_error.error_time > max_error_time {
+            hold_error.error_time = max_error_time;
         }
         let speed = error / hold_error.error_time;
         if speed > shadow.max_speed {
             // this seems like it would still result in a speed above max_speed
             // but idk.
-            error *= 0.5;
+            error *= config.hold.fast_error_scale;
         }
         hold_error.error = hold_error.error.lerp(error, hold_error.error_time);
         hold_error.error_time = 0.0;
+
+        let max_error = max_error.map(|m| m.0).unwrap_or(config.hold.max_error);
+        let strained_fraction = strained_fraction
+            .map(|f| f.0)
+            .unwrap_or(config.hold.strained_fraction);
+        if update_strain(&mut hold_error, max_error, strained_fraction) {
+            for holder in holders.holders.iter() {
+                w_strained_event.send(HoldStrained {
+                    prop,
+                    actor: holder.actor,
+                });
+            }
+        }
+    }
+}
+
+/// Updates the strain from the current error.
+/// Returns whether the hold just became strained.
+fn update_strain(hold_error: &mut HoldError, max_error: f32, strained_fraction: f32) -> bool {
+    hold_error.strain = if max_error > 0.0 {
+        (hold_error.error / max_error).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    let strained = hold_error.strain >= strained_fraction;
+    let just_strained = strained && !hold_error.strained;
+    hold_error.strained = strained;
+    just_strained
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strain_is_fraction_of_max_error() {
+        let mut hold_error = HoldError::new(0.0);
+        hold_error.error = 0.25;
+        update_strain(&mut hold_error, 1.0, 0.75);
+        assert_eq!(hold_error.strain(), 0.25);
+        hold_error.error = 2.0;
+        update_strain(&mut hold_error, 1.0, 0.75);
+        assert_eq!(hold_error.strain(), 1.0);
+        // Without any tolerance, every hold is fully strained.
+        hold_error.error = 0.0;
+        update_strain(&mut hold_error, 0.0, 0.75);
+        assert_eq!(hold_error.strain(), 1.0);
+    }
+
+    #[test]
+    fn strained_is_reported_once_per_crossing() {
+        let mut hold_error = HoldError::new(0.0);
+        let mut step = |error| {
+            hold_error.error = error;
+            update_strain(&mut hold_error, 1.0, 0.75)
+        };
+        assert!(!step(0.5));
+        assert!(step(0.75));
+        // Staying above the threshold does not report again.
+        assert!(!step(0.9));
+        // Going back below the threshold allows reporting the next crossing.
+        assert!(!step(0.5));
+        assert!(step(0.8));
     }
 }
@@ -1,11 +1,17 @@
-use avian3d::sync::ancestor_marker::AncestorMarker;
+use avian::{
+    math::{Scalar, Vector},
+    sync::ancestor_marker::AncestorMarker,
+};
 
 use super::{prelude::*, HoldSystem};
 use crate::{
-    math::{rigid_body_compound_collider, GetBestGlobalTransform as _},
+    interaction::RigidBodyFilterParams,
+    math::{
+        actor_forward, actor_side, clamp_actor_pitch, rigid_body_compound_collider, to_dir,
+        to_rotation_value, GetBestGlobalTransform as _, ToQuat as _, ToVector as _,
+    },
     prelude::*,
-    prop::PrePickupRotation,
-    verb::{Holding, SetVerb, Verb},
+    verb::{SetVerb, Verb},
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -16,158 +22,167 @@ pub(super) fn plugin(app: &mut App) {
 fn set_targets(
     mut commands: Commands,
     spatial_query: SpatialQuery,
-    mut q_actor: Query<(
-        Entity,
-        &AvianPickupActor,
-        &HoldError,
-        &mut ShadowParams,
-        &Holding,
-    )>,
+    q_actor: Query<(Entity, &AvianPickupActor, &AvianPickupActorHeldProps)>,
     q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
     mut q_prop: Query<(
+        &HoldError,
+        &mut Holders,
         &Rotation,
-        Option<&PrePickupRotation>,
         Option<&PreferredPickupRotation>,
         Option<&PreferredPickupDistanceOverride>,
         Option<&PitchRangeOverride>,
+        Option<&MaxHoldErrorOverride>,
     )>,
 
     q_collider_ancestor: Query<&Children, With<AncestorMarker<ColliderMarker>>>,
     q_collider: Query<(&Transform, &Collider, Option<&CollisionLayers>)>,
+    rigid_body_filter: RigidBodyFilterParams,
 ) {
-    let max_error = 0.3048; // 12 inches in the source engine
-    for (actor, config, hold_error, mut shadow, holding) in q_actor.iter_mut() {
-        let prop = holding.0;
-        if hold_error.error > max_error {
-            commands
-                .entity(actor)
-                .add(SetVerb::new(Verb::Drop { prop, forced: true }));
-            continue;
-        }
+    for (actor, config, held_props) in q_actor.iter() {
         let actor_transform = q_actor_transform.get_best_global_transform(actor);
-
-        let Ok((
-            prop_rotation,
-            pre_pickup_rotation,
-            preferred_rotation,
-            preferred_distance,
-            clamp_pitch,
-        )) = q_prop.get_mut(prop)
-        else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
-            continue;
-        };
-        let pitch_range = clamp_pitch
-            .map(|c| &c.0)
-            .unwrap_or(&config.hold.pitch_range);
-        let (actor_yaw, actor_pitch, actor_roll) = actor_transform.rotation.to_euler(EulerRot::YXZ);
-        let actor_to_prop_pitch = actor_pitch.clamp(*pitch_range.start(), *pitch_range.end());
-        let clamped_rotation =
-            Quat::from_euler(EulerRot::YXZ, actor_yaw, actor_to_prop_pitch, actor_roll);
-        let forward = Transform::from_rotation(clamped_rotation).forward();
-        // We can't cast a ray wrt an entire rigid body out of the box,
-        // so we manually collect all colliders in the hierarchy and
-        // construct a compound collider.
-        let prop_collider = rigid_body_compound_collider(
-            prop,
-            &q_collider_ancestor,
-            &q_collider,
-            &config.prop_filter,
-        );
-        let Some(prop_collider) = prop_collider else {
-            error!("Held prop does not have a collider in its hierarchy. Ignoring.");
-            continue;
-        };
-        let prop_radius_wrt_direction =
-            collide_get_extent(&prop_collider, Vec3::ZERO, prop_rotation.0, -forward);
-        let actor_collider = rigid_body_compound_collider(
-            actor,
-            &q_collider_ancestor,
-            &q_collider,
-            &config.actor_filter,
-        );
-        let actor_radius_wrt_direction = if let Some(actor_collider) = actor_collider {
-            let min_distance_to_not_penetrate = collide_get_extent(
-                &actor_collider,
+        let slot_count = held_props.len();
+        for (slot, prop) in held_props.iter().enumerate() {
+            let Ok((
+                hold_error,
+                mut holders,
+                prop_rotation,
+                preferred_rotation,
+                preferred_distance,
+                clamp_pitch,
+                max_error,
+            )) = q_prop.get_mut(prop)
+            else {
+                error!("Prop entity was deleted or in an invalid state. Ignoring.");
+                continue;
+            };
+            let max_error = max_error.map(|m| m.0).unwrap_or(config.hold.max_error);
+            if hold_error.error > max_error
+                || !rigid_body_filter.allows(&config.rigid_body_filter, prop)
+            {
+                commands
+                    .entity(actor)
+                    .add(SetVerb::new(Verb::Drop { prop, forced: true }));
+                continue;
+            }
+            let Some(holder) = holders.get_mut(actor) else {
+                error!("Held prop does not know that it is being held by the actor. Ignoring.");
+                continue;
+            };
+            let pitch_range = clamp_pitch
+                .map(|c| &c.0)
+                .unwrap_or(&config.hold.pitch_range);
+            let clamped_rotation = clamp_actor_pitch(actor_transform.rotation, pitch_range);
+            let forward = actor_forward(clamped_rotation);
+            // Line up multiple held props next to each other, centered on the actor's forward.
+            let slot_offset =
+                (slot as Scalar - (slot_count - 1) as Scalar / 2.0) * config.hold.held_prop_spacing;
+            let origin =
+                actor_transform.translation + actor_side(actor_transform.rotation) * slot_offset;
+            // We can't cast a ray wrt an entire rigid body out of the box,
+            // so we manually collect all colliders in the hierarchy and
+            // construct a compound collider.
+            let prop_collider = rigid_body_compound_collider(
+                prop,
+                &q_collider_ancestor,
+                &q_collider,
+                &config.prop_filter,
+            );
+            let Some(prop_collider) = prop_collider else {
+                error!("Held prop does not have a collider in its hierarchy. Ignoring.");
+                continue;
+            };
+            let prop_radius_wrt_direction = collide_get_extent(
+                &prop_collider,
                 Vec3::ZERO,
-                actor_transform.rotation,
-                forward,
+                prop_rotation.to_quat(),
+                -forward,
             );
-            min_distance_to_not_penetrate.max(config.hold.min_distance)
-        } else {
-            config.hold.min_distance
-        };
-
-        let min_distance = prop_radius_wrt_direction + actor_radius_wrt_direction;
-        // The 2013 code now additionally does `min_distance = (min_distance * 2) + 24
-        // inches` That seems straight up bizarre, so I refuse to do that.
-        let preferred_distance = preferred_distance
-            .map(|d| d.0)
-            .unwrap_or(config.hold.preferred_distance);
-        // The 2013 code does `max_distance = preferred_distance + min_distance`
-        // which means that `preferred_distance` is the distance between the prop's
-        // edge and the actors's edge. Expect psyche, actually `min_distance` gets
-        // deduced again at some point! I think it's more intuitive to have the
-        // preferred distance be the distance between the prop's and
-        // actor's origins if possible instead.
-        let max_distance = preferred_distance.max(min_distance);
-
-        let Some(actor_space_rotation) = preferred_rotation
-            .map(|preferred| preferred.0)
-            .or_else(|| pre_pickup_rotation.map(|pre| pre.0))
-        else {
-            error!("Held prop does not have a preferred or pre-pickup rotation. Ignoring.");
-            continue;
-        };
-        // orient the prop wrt the actor
-        // The 2013 code uses the non-clamped code here, resulting in the prop
-        // rotating when looking further up than the clamp allows.
-        // Looks weird imo, so we use the clamped rotation.
-        let clamped_actor_transform = actor_transform.with_rotation(clamped_rotation);
-        let target_rotation =
-            prop_rotation_from_actor_space(actor_space_rotation, clamped_actor_transform);
-
-        shadow.target_rotation = target_rotation;
-
-        // The cast needs to be longer to account for the fact that
-        // the prop might hit terrain with the side that is not facing
-        // the player. We are assuming the prop has the same radius
-        // "behind" it as it has in front of it. Also add a bit of
-        // padding to be safe.
-        let max_cast_toi = max_distance + min_distance + 0.5;
-
-        // Not filtering this out later because we want the cast to "pass through" the
-        // prop to get the distance to the terrain behind it.
-        let mut terrain_filter = config.obstacle_filter.clone();
-        terrain_filter.excluded_entities.insert(prop);
-        let terrain_hit = spatial_query.cast_shape(
-            &prop_collider,
-            actor_transform.translation,
-            target_rotation,
-            forward,
-            max_cast_toi,
-            true,
-            terrain_filter,
-        );
-        let distance = if let Some(terrain_hit) = terrain_hit {
-            let toi = terrain_hit.time_of_impact;
-            let fraction = toi / max_distance;
-            if fraction < 0.5 {
-                // not doing `max(min_distance, toi)` here because that would
-                // result in the prop being too close to the player
-                // better to intersect with the terrain than to the player.
-                min_distance
+            let actor_collider = rigid_body_compound_collider(
+                actor,
+                &q_collider_ancestor,
+                &q_collider,
+                &config.actor_filter,
+            );
+            let actor_radius_wrt_direction = if let Some(actor_collider) = actor_collider {
+                let min_distance_to_not_penetrate = collide_get_extent(
+                    &actor_collider,
+                    Vec3::ZERO,
+                    actor_transform.rotation,
+                    forward,
+                );
+                min_distance_to_not_penetrate.max(config.hold.min_distance)
             } else {
-                max_distance.min(toi)
-            }
-        } else {
-            max_distance
-        };
-        // Pretty sure we don't need to go through the CalcClosestPointOnLine song and
-        // dance since we already have made sure that the prop has a sensible minimum
-        // distance
-        let target_position = actor_transform.translation + forward * distance;
-        shadow.target_position = target_position;
+                config.hold.min_distance
+            };
+
+            let min_distance = prop_radius_wrt_direction + actor_radius_wrt_direction;
+            // The 2013 code now additionally does `min_distance = (min_distance * 2) + 24
+            // inches` That seems straight up bizarre, so I refuse to do that.
+            let preferred_distance = preferred_distance
+                .map(|d| d.0)
+                .unwrap_or(config.hold.preferred_distance);
+            // The 2013 code does `max_distance = preferred_distance + min_distance`
+            // which means that `preferred_distance` is the distance between the prop's
+            // edge and the actors's edge. Expect psyche, actually `min_distance` gets
+            // deduced again at some point! I think it's more intuitive to have the
+            // preferred distance be the distance between the prop's and
+            // actor's origins if possible instead.
+            let max_distance = preferred_distance.max(min_distance);
+
+            let actor_space_rotation = preferred_rotation
+                .map(|preferred| preferred.0)
+                .unwrap_or(holder.pre_pickup_rotation);
+            // orient the prop wrt the actor
+            // The 2013 code uses the non-clamped code here, resulting in the prop
+            // rotating when looking further up than the clamp allows.
+            // Looks weird imo, so we use the clamped rotation.
+            let clamped_actor_transform = actor_transform.with_rotation(clamped_rotation);
+            let target_rotation =
+                prop_rotation_from_actor_space(actor_space_rotation, clamped_actor_transform);
+
+            holder.target_rotation = target_rotation;
+
+            // The cast needs to be longer to account for the fact that
+            // the prop might hit terrain with the side that is not facing
+            // the player. We are assuming the prop has the same radius
+            // "behind" it as it has in front of it. Also add a bit of
+            // padding to be safe.
+            let max_cast_toi = max_distance + min_distance + 0.5;
+
+            // Not filtering this out later because we want the cast to "pass through" the
+            // prop to get the distance to the terrain behind it.
+            // The other held props are also ignored so that they don't push each other away.
+            let mut terrain_filter = config.obstacle_filter.clone();
+            terrain_filter.excluded_entities.extend(held_props.iter());
+            let terrain_hit = spatial_query.cast_shape(
+                &prop_collider,
+                origin.to_vector(),
+                to_rotation_value(target_rotation),
+                to_dir(forward),
+                max_cast_toi,
+                true,
+                terrain_filter,
+            );
+            let distance = if let Some(terrain_hit) = terrain_hit {
+                let toi = terrain_hit.time_of_impact;
+                let fraction = toi / max_distance;
+                if fraction < 0.5 {
+                    // not doing `max(min_distance, toi)` here because that would
+                    // result in the prop being too close to the player
+                    // better to intersect with the terrain than to the player.
+                    min_distance
+                } else {
+                    max_distance.min(toi)
+                }
+            } else {
+                max_distance
+            };
+            // Pretty sure we don't need to go through the CalcClosestPointOnLine song and
+            // dance since we already have made sure that the prop has a sensible minimum
+            // distance
+            let target_position = origin + forward * distance;
+            holder.target_position = target_position;
+        }
     }
 }
 
@@ -179,7 +194,7 @@ fn set_targets(
 /// the direction and the support point, it looks like the result is the same.
 /// That's why we just return the TOI directly.
 fn collide_get_extent(collider: &Collider, origin: Vec3, rotation: Quat, dir: Dir3) -> f32 {
-    const TRANSLATION: Vec3 = Vec3::ZERO;
+    const TRANSLATION: Vector = Vector::ZERO;
     // We cast from inside the collider, so we don't care about a max TOI
     const MAX_TOI: f32 = f32::INFINITY;
     // Needs to be false to not just get the origin back
@@ -189,7 +204,14 @@ fn collide_get_extent(collider: &Collider, origin: Vec3, rotation: Quat, dir: Di
     // bug sometimes causes the hit to be `None` even though that should
     // be impossible: https://discord.com/channels/691052431525675048/1124043933886976171/1275214643341561970
     const ARBITRARY_ROTATION: f32 = 5e-3;
-    for offset in [
+    #[cfg(feature = "2d")]
+    let offsets = [
+        Quat::IDENTITY,
+        Quat::from_rotation_z(ARBITRARY_ROTATION),
+        Quat::from_rotation_z(-ARBITRARY_ROTATION),
+    ];
+    #[cfg(feature = "3d")]
+    let offsets = [
         Quat::IDENTITY,
         Quat::from_rotation_x(ARBITRARY_ROTATION),
         Quat::from_rotation_x(-ARBITRARY_ROTATION),
@@ -197,9 +219,17 @@ fn collide_get_extent(collider: &Collider, origin: Vec3, rotation: Quat, dir: Di
         Quat::from_rotation_y(-ARBITRARY_ROTATION),
         Quat::from_rotation_z(ARBITRARY_ROTATION),
         Quat::from_rotation_z(-ARBITRARY_ROTATION),
-    ] {
-        let rotation = rotation * offset;
-        let hit = collider.cast_ray(TRANSLATION, rotation, origin, dir.into(), MAX_TOI, SOLID);
+    ];
+    for offset in offsets {
+        let rotation = to_rotation_value(rotation * offset);
+        let hit = collider.cast_ray(
+            TRANSLATION,
+            rotation,
+            origin.to_vector(),
+            dir.to_vector(),
+            MAX_TOI,
+            SOLID,
+        );
         if let Some((toi, _normal)) = hit {
             return toi;
         }
@@ -207,7 +237,7 @@ fn collide_get_extent(collider: &Collider, origin: Vec3, rotation: Quat, dir: Di
 
     // Absolute last resort: just fall back to the AABB's longest extent.
     // This *must* work, but it's longer than necessary and expensive.
-    let aabb = collider.aabb(origin, rotation);
+    let aabb = collider.aabb(origin.to_vector(), to_rotation_value(rotation));
 
     (aabb.max / 2.).length()
 }
@@ -224,6 +254,7 @@ fn prop_rotation_from_actor_space(rot: Quat, actor: Transform) -> Quat {
 mod test {
     use super::*;
 
+    #[cfg(feature = "3d")]
     #[test]
     fn test_collide_get_extent() {
         let collider = Collider::capsule(0.3, 1.2);
@@ -236,6 +267,7 @@ mod test {
         assert!(extent < 0.6);
     }
 
+    #[cfg(feature = "3d")]
     #[test]
     #[ignore = "Parry bug"]
     fn test_collider_get_extent_manual() {
@@ -243,7 +275,7 @@ mod test {
         let rotation = Quat::from_euler(EulerRot::YXZ, -0.014999974, -0.07314853, 0.);
         let dir = Vec3::new(0.014959301, -0.073083326, -0.9972137);
 
-        const TRANSLATION: Vec3 = Vec3::ZERO;
+        const TRANSLATION: Vector = Vector::ZERO;
         const ORIGIN: Vec3 = Vec3::ZERO;
         // We cast from inside the collider, so we don't care about a max TOI
         const MAX_TOI: f32 = f32::INFINITY;
@@ -1,12 +1,25 @@
 use bevy::prelude::*;
 
 mod drop;
+mod filter;
 mod hold;
 mod pull;
+mod punt;
 mod throw;
 
-pub(crate) use self::hold::prelude::*;
+pub use self::hold::prelude::{HoldError, ShadowParams};
+pub(crate) use self::{
+    filter::RigidBodyFilterParams,
+    hold::prelude::*,
+    throw::{intercept_velocity, launch_speed, target_motion},
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((hold::plugin, pull::plugin, drop::plugin, throw::plugin));
+    app.add_plugins((
+        hold::plugin,
+        pull::plugin,
+        drop::plugin,
+        throw::plugin,
+        punt::plugin,
+    ));
 }
@@ -0,0 +1,118 @@
+use bevy::ecs::system::SystemParam;
+
+use super::{arc::*, PullForce};
+use crate::{
+    math::{ToVec3 as _, ToVector as _},
+    prelude::*,
+};
+
+/// The system params shared by everything that pulls props.
+#[derive(SystemParam)]
+pub(super) struct PullParams<'w, 's> {
+    pub(super) spatial_query: SpatialQuery<'w, 's>,
+    pub(super) gravity: Res<'w, Gravity>,
+    pub(super) q_collider_parent: Query<'w, 's, &'static ColliderParent>,
+}
+
+/// A dynamic prop that is about to be pulled.
+pub(super) struct PulledProp<'a> {
+    pub(super) entity: Entity,
+    pub(super) position: Vec3,
+    pub(super) mass: Mass,
+    pub(super) velocity: Vec3,
+    pub(super) gravity_scale: Option<&'a GravityScale>,
+}
+
+/// How a prop is pulled, as calculated by [`PullParams::pull`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Pull {
+    /// Apply this impulse once, see [`AvianPickupPullMode::Impulse`].
+    Impulse(Vec3),
+    /// Set this force for the current physics step, see
+    /// [`AvianPickupPullMode::Force`].
+    Force(Vec3),
+}
+
+impl PullParams<'_, '_> {
+    /// Calculates how to pull the prop towards the actor, as configured by
+    /// [`AvianPickupActorPullConfig`]. Nothing is applied yet, see
+    /// [`Pull::apply`].
+    pub(super) fn pull(
+        &self,
+        config: &AvianPickupActor,
+        actor_position: Vec3,
+        prop: PulledProp,
+    ) -> Pull {
+        let up = Dir3::new(-self.gravity.0.to_vec3()).ok();
+        let arc_up = up.filter(|_| config.pull.lift_height > 0.0);
+        let direction = if let Some(up) = arc_up {
+            arc_direction(
+                &self.spatial_query,
+                config,
+                prop.entity,
+                prop.position,
+                actor_position,
+                up,
+                &self.q_collider_parent,
+            )
+        } else {
+            (actor_position - prop.position).normalize_or_zero()
+        };
+        let compensation = if arc_up.is_some() {
+            gravity_compensation(&self.gravity, prop.gravity_scale, prop.mass)
+        } else {
+            Vec3::ZERO
+        };
+        let mass_adjustment = config.pull.mass_adjustment.factor(prop.mass.0);
+        match config.pull.mode {
+            AvianPickupPullMode::Impulse => {
+                // Cancel out the gravity until the next impulse.
+                Pull::Impulse(
+                    direction * config.pull.impulse * mass_adjustment
+                        + compensation * config.cooldown.pull,
+                )
+            }
+            AvianPickupPullMode::Force {
+                force: pull_force_magnitude,
+                max_speed,
+            } => {
+                let speed_limit_factor = max_speed.map_or(1.0, |max_speed| {
+                    let speed_towards_actor = prop.velocity.dot(direction);
+                    (1.0 - speed_towards_actor / max_speed).clamp(0.0, 1.0)
+                });
+                Pull::Force(
+                    direction * pull_force_magnitude * mass_adjustment * speed_limit_factor
+                        + compensation,
+                )
+            }
+        }
+    }
+}
+
+impl Pull {
+    /// Applies the pull to the prop. Handling the cooldown and the actor's
+    /// verb is up to the caller, as a single pull can affect multiple props.
+    pub(super) fn apply(self, world: &mut World, prop: Entity) {
+        let Some(mut prop) = world.get_entity_mut(prop) else {
+            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+            return;
+        };
+        match self {
+            Pull::Impulse(pull_impulse) => {
+                if let Some(mut impulse) = prop.get_mut::<ExternalImpulse>() {
+                    impulse.apply_impulse(pull_impulse.to_vector());
+                }
+            }
+            Pull::Force(pull_force) => {
+                if let Some(mut force) = prop.get_mut::<ExternalForce>() {
+                    force.set_force(pull_force.to_vector());
+                }
+                if let Some(mut pull_force_marker) = prop.get_mut::<PullForce>() {
+                    pull_force_marker.applied = true;
+                } else {
+                    prop.insert(PullForce { applied: true });
+                }
+            }
+        }
+    }
+}
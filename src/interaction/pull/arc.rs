@@ -0,0 +1,53 @@
+use crate::{
+    math::{to_dir, ToVec3 as _, ToVector as _},
+    prelude::*,
+};
+
+/// The direction to pull a prop in when
+/// [`AvianPickupActorPullConfig::lift_height`] is set.
+/// Props are first lifted until their center is `lift_height` above the ground
+/// below them. Afterwards, they are pulled towards a point that lies above the
+/// actor while they are far away and sinks down to the actor as they get close,
+/// so that they travel along an arc.
+pub(super) fn arc_direction(
+    spatial_query: &SpatialQuery,
+    config: &AvianPickupActor,
+    prop: Entity,
+    prop_position: Vec3,
+    actor_position: Vec3,
+    up: Dir3,
+    q_collider_parent: &Query<&ColliderParent>,
+) -> Vec3 {
+    let lift_height = config.pull.lift_height;
+    let ground_hit = spatial_query.cast_ray_predicate(
+        prop_position.to_vector(),
+        to_dir(-up),
+        lift_height,
+        true,
+        &config.obstacle_filter,
+        // The prop's own colliders are not the ground.
+        &|entity| {
+            q_collider_parent
+                .get(entity)
+                .map_or(true, |parent| parent.get() != prop)
+        },
+    );
+    if ground_hit.is_some() {
+        return up.into();
+    }
+    let to_actor = actor_position - prop_position;
+    let height = to_actor.dot(*up);
+    let horizontal_distance = (to_actor - up * height).length();
+    let aim = actor_position + up * horizontal_distance.min(lift_height);
+    (aim - prop_position).normalize_or_zero()
+}
+
+/// The impulse or force needed to cancel out gravity while pulling.
+pub(super) fn gravity_compensation(
+    gravity: &Gravity,
+    gravity_scale: Option<&GravityScale>,
+    mass: Mass,
+) -> Vec3 {
+    let scale = gravity_scale.map_or(1.0, |scale| scale.0);
+    -gravity.0.to_vec3() * scale * mass.0
+}
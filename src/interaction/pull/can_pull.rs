@@ -1,6 +1,17 @@
 use crate::prelude::*;
 
 /// Inspired by [`CWeaponPhysCannon::CanPickupObject`](https://github.com/ValveSoftware/source-sdk-2013/blob/master/mp/src/game/server/hl2/weapon_physcannon.cpp#L3421)
-pub(super) fn can_pull(rigid_body: RigidBody, mass: Mass, config: &AvianPickupActor) -> bool {
-    rigid_body == RigidBody::Dynamic && mass.0 < config.pull.max_prop_mass
+pub(super) fn can_pull(
+    rigid_body: RigidBody,
+    dynamic_while_held: bool,
+    mass: Mass,
+    config: &AvianPickupActor,
+) -> bool {
+    can_become_dynamic(rigid_body, dynamic_while_held) && mass.0 < config.pull.max_prop_mass
+}
+
+/// Non-dynamic props can only be picked up if they are marked with
+/// [`DynamicWhileHeld`].
+pub(super) fn can_become_dynamic(rigid_body: RigidBody, dynamic_while_held: bool) -> bool {
+    rigid_body == RigidBody::Dynamic || dynamic_while_held
 }
@@ -0,0 +1,72 @@
+use super::{can_pull::*, find_prop, PropSearchData};
+use crate::{interaction::RigidBodyFilterParams, math::GetBestGlobalTransform, prelude::*};
+
+/// Keeps [`PickupCandidate`] up to date by running the same search as a pull
+/// would, but without affecting any props.
+pub(super) fn update_candidates(
+    spatial_query: SpatialQuery,
+    mut q_actor: Query<(
+        Entity,
+        &AvianPickupActor,
+        &AvianPickupActorState,
+        &AvianPickupActorHeldProps,
+        &mut PickupCandidate,
+        Option<&CustomPropTargeting>,
+    )>,
+    q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
+    q_collider_parent: Query<&ColliderParent>,
+    q_rigid_body: Query<
+        (
+            &RigidBody,
+            &Mass,
+            Has<HeldProp>,
+            Has<CooperativeHold>,
+            Has<DynamicWhileHeld>,
+        ),
+        Without<AbsorbedProp>,
+    >,
+    q_collider: Query<PropSearchData>,
+    rigid_body_filter: RigidBodyFilterParams,
+    mut w_candidate_event: EventWriter<CandidateChanged>,
+) {
+    for (actor, config, state, held_props, mut candidate, custom_targeting) in q_actor.iter_mut() {
+        let current = if state.is_idle() {
+            let actor_transform = q_actor_transform.get_best_global_transform(actor);
+            find_prop(
+                &spatial_query,
+                actor_transform,
+                config.interaction_distance,
+                config.hold.distance_to_allow_holding,
+                config,
+                held_props,
+                custom_targeting,
+                &q_collider,
+                &rigid_body_filter,
+            )
+            .and_then(|prop| q_collider_parent.get(prop.entity).ok())
+            .map(|rigid_body| rigid_body.get())
+            .filter(|&rigid_body| {
+                q_rigid_body.get(rigid_body).is_ok_and(
+                    |(&rigid_body, &mass, is_held, is_cooperative, dynamic_while_held)| {
+                        // Same rules as in `find_object`
+                        let can_join =
+                            is_cooperative && can_become_dynamic(rigid_body, dynamic_while_held);
+                        let is_pullable =
+                            !is_held && can_pull(rigid_body, dynamic_while_held, mass, config);
+                        is_pullable || can_join
+                    },
+                )
+            })
+        } else {
+            None
+        };
+        if candidate.0 != current {
+            w_candidate_event.send(CandidateChanged {
+                actor,
+                previous: candidate.0,
+                current,
+            });
+            candidate.0 = current;
+        }
+    }
+}
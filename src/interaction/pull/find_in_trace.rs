@@ -1,31 +1,55 @@
-use super::Prop;
-use crate::prelude::*;
+use super::{
+    find_prop::{is_held_collider, rigid_body_of, PropSearchData},
+    Prop,
+};
+use crate::{
+    interaction::RigidBodyFilterParams,
+    math::{actor_forward, box_collider, to_dir, to_rotation_value, ToVector as _},
+    prelude::*,
+};
 
 /// Inspired by [`CWeaponPhysCannon::FindObjectTrace`](https://github.com/ValveSoftware/source-sdk-2013/blob/master/mp/src/game/server/hl2/weapon_physcannon.cpp#L2470)
 pub(super) fn find_prop_in_trace(
     spatial_query: &SpatialQuery,
     origin: Transform,
+    test_length: f32,
     config: &AvianPickupActor,
+    held_props: &AvianPickupActorHeldProps,
+    q_collider: &Query<PropSearchData>,
+    rigid_body_filter: &RigidBodyFilterParams,
 ) -> Option<Prop> {
-    // Fun fact: Valve lies to you and actually multiplies this by 4 at this point.
-    let test_length = config.interaction_distance;
-    let hit = spatial_query.cast_ray(
-        origin.translation,
-        origin.forward(),
+    // Props we are already holding are right in front of us, so look past them.
+    let is_held = |entity| is_held_collider(q_collider, held_props, entity);
+    let allows = |entity| {
+        !is_held(entity) && rigid_body_filter.allows_collider(&config.rigid_body_filter, entity)
+    };
+    // Other colliders of the prop we hit are not terrain in front of it.
+    let is_same_prop = |a: Entity, b: Entity| {
+        a == b
+            || rigid_body_of(q_collider, a).is_some_and(|a| rigid_body_of(q_collider, b) == Some(a))
+    };
+    let forward = to_dir(actor_forward(origin.rotation));
+    let translation = origin.translation.to_vector();
+    let rotation = to_rotation_value(origin.rotation);
+    let hit = spatial_query.cast_ray_predicate(
+        translation,
+        forward,
         test_length,
         true,
         &config.prop_filter,
+        &allows,
     );
 
-    hit.filter(|hit| {
-        if let Some(terrain_hit) = spatial_query.cast_ray(
-            origin.translation,
-            origin.forward(),
+    let hit = hit.filter(|hit| {
+        if let Some(terrain_hit) = spatial_query.cast_ray_predicate(
+            translation,
+            forward,
             test_length,
             true,
             &config.obstacle_filter,
+            &|entity| !is_held(entity),
         ) {
-            let occluded = terrain_hit.entity != hit.entity
+            let occluded = !is_same_prop(terrain_hit.entity, hit.entity)
                 && terrain_hit.time_of_impact <= hit.time_of_impact;
             !occluded
         } else {
@@ -43,27 +67,40 @@ pub(super) fn find_prop_in_trace(
         // This has a half-extent of 4 inches in the 2013 code, which is about 1 cm
         const MAGIC_HALF_EXTENT_ASK_VALVE: f32 = 0.01;
         let fake_aabb_because_parry_cannot_do_aabb_casts =
-            Cuboid::from_size(Vec3::splat(2. * MAGIC_HALF_EXTENT_ASK_VALVE)).into();
-        let hit = spatial_query.cast_shape(
-            &fake_aabb_because_parry_cannot_do_aabb_casts,
-            origin.translation,
-            origin.rotation,
-            origin.forward(),
-            test_length,
-            false,
-            &config.prop_filter,
-        );
-        hit.filter(|hit| {
-            if let Some(terrain_hit) = spatial_query.cast_shape(
+            box_collider(MAGIC_HALF_EXTENT_ASK_VALVE);
+        // There is no predicate version of `cast_shape`, so we collect all hits
+        // and pick the nearest one passing the filter ourselves.
+        let hit = spatial_query
+            .shape_hits(
                 &fake_aabb_because_parry_cannot_do_aabb_casts,
-                origin.translation,
-                origin.rotation,
-                origin.forward(),
+                translation,
+                rotation,
+                forward,
                 test_length,
+                u32::MAX,
                 false,
-                &config.obstacle_filter,
-            ) {
-                let occluded = terrain_hit.entity != hit.entity
+                &config.prop_filter,
+            )
+            .into_iter()
+            .filter(|hit| allows(hit.entity))
+            .min_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact));
+        hit.filter(|hit| {
+            let terrain_hit = spatial_query
+                .shape_hits(
+                    &fake_aabb_because_parry_cannot_do_aabb_casts,
+                    translation,
+                    rotation,
+                    forward,
+                    test_length,
+                    u32::MAX,
+                    false,
+                    &config.obstacle_filter,
+                )
+                .into_iter()
+                .filter(|terrain_hit| !is_held(terrain_hit.entity))
+                .min_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact));
+            if let Some(terrain_hit) = terrain_hit {
+                let occluded = !is_same_prop(terrain_hit.entity, hit.entity)
                     && terrain_hit.time_of_impact <= hit.time_of_impact;
                 !occluded
             } else {
@@ -0,0 +1,297 @@
+use avian::math::Scalar;
+use bevy::ecs::query::QueryData;
+
+use super::{find_prop_in_cone, find_prop_in_trace, Prop};
+use crate::{
+    interaction::RigidBodyFilterParams,
+    math::{
+        actor_forward, sphere_collider, to_dir, to_rotation_value, ToQuat as _, ToVec3 as _,
+        ToVector as _,
+    },
+    prelude::*,
+};
+
+/// What the prop searches need to know about colliders and their rigid bodies.
+#[derive(QueryData)]
+pub(crate) struct PropSearchData {
+    position: &'static Position,
+    rotation: Option<&'static Rotation>,
+    center_of_mass: Option<&'static CenterOfMass>,
+    collider_parent: Option<&'static ColliderParent>,
+}
+
+/// Returns the rigid body the collider belongs to.
+pub(super) fn rigid_body_of(
+    q_collider: &Query<PropSearchData>,
+    collider: Entity,
+) -> Option<Entity> {
+    q_collider
+        .get(collider)
+        .ok()?
+        .collider_parent
+        .map(ColliderParent::get)
+}
+
+/// Whether the collider belongs to a prop the actor is holding.
+pub(super) fn is_held_collider(
+    q_collider: &Query<PropSearchData>,
+    held_props: &AvianPickupActorHeldProps,
+    collider: Entity,
+) -> bool {
+    rigid_body_of(q_collider, collider).is_some_and(|rigid_body| held_props.contains(rigid_body))
+}
+
+/// Returns the world space center of mass of the rigid body.
+fn center_of(q_collider: &Query<PropSearchData>, rigid_body: Entity) -> Option<Vec3> {
+    let data = q_collider.get(rigid_body).ok()?;
+    let position = data.position.0.to_vec3();
+    let Some((rotation, center_of_mass)) = data.rotation.zip(data.center_of_mass) else {
+        return Some(position);
+    };
+    Some(position + rotation.to_quat() * center_of_mass.0.to_vec3())
+}
+
+/// A rigid body found by a prop search.
+pub(super) struct RigidBodyHit {
+    /// The first collider of the rigid body that was found.
+    pub(super) collider: Entity,
+    pub(super) rigid_body: Entity,
+    /// The world space center of mass of the rigid body.
+    pub(super) center: Vec3,
+}
+
+/// Groups the colliders by the rigid body they belong to, so that a prop made
+/// out of many colliders is only considered once. Rigid bodies not passing
+/// [`AvianPickupActor::rigid_body_filter`] and props the actor is already
+/// holding are left out.
+pub(super) fn group_by_rigid_body(
+    colliders: impl IntoIterator<Item = Entity>,
+    config: &AvianPickupActor,
+    held_props: &AvianPickupActorHeldProps,
+    q_collider: &Query<PropSearchData>,
+    rigid_body_filter: &RigidBodyFilterParams,
+) -> Vec<RigidBodyHit> {
+    let mut hits: Vec<RigidBodyHit> = Vec::new();
+    for collider in colliders {
+        let Some(rigid_body) = rigid_body_of(q_collider, collider) else {
+            continue;
+        };
+        if hits.iter().any(|hit| hit.rigid_body == rigid_body)
+            || held_props.contains(rigid_body)
+            || !rigid_body_filter.allows(&config.rigid_body_filter, rigid_body)
+        {
+            continue;
+        }
+        let Some(center) = center_of(q_collider, rigid_body) else {
+            continue;
+        };
+        hits.push(RigidBodyHit {
+            collider,
+            rigid_body,
+            center,
+        });
+    }
+    hits
+}
+
+/// Checks whether the rigid body can be seen from `origin` when looking in the
+/// direction `los` for `dist`, and returns the time of impact of the ray
+/// hitting it if so. The rigid body's own colliders and the props the actor is
+/// holding never occlude it.
+pub(super) fn unoccluded_time_of_impact(
+    spatial_query: &SpatialQuery,
+    origin: Vec3,
+    los: Dir3,
+    dist: Scalar,
+    config: &AvianPickupActor,
+    held_props: &AvianPickupActorHeldProps,
+    rigid_body: Entity,
+    q_collider: &Query<PropSearchData>,
+    rigid_body_filter: &RigidBodyFilterParams,
+) -> Option<Scalar> {
+    let is_own = |entity| rigid_body_of(q_collider, entity) == Some(rigid_body);
+    let is_held = |entity| is_held_collider(q_collider, held_props, entity);
+
+    // Make sure it isn't occluded by terrain
+    let terrain_hit = spatial_query.cast_ray_predicate(
+        origin.to_vector(),
+        to_dir(los),
+        dist,
+        true,
+        &config.obstacle_filter,
+        &|entity| !is_own(entity) && !is_held(entity),
+    );
+    if terrain_hit.is_some() {
+        return None;
+    }
+
+    // Make sure it isn't occluded by other props
+    let hit = spatial_query.cast_ray_predicate(
+        origin.to_vector(),
+        to_dir(los),
+        dist,
+        true,
+        &config.prop_filter,
+        // Props that don't pass the filter are ignored, so they don't occlude either.
+        &|entity| {
+            !is_held(entity) && rigid_body_filter.allows_collider(&config.rigid_body_filter, entity)
+        },
+    );
+    match hit {
+        Some(hit) if is_own(hit.entity) => Some(hit.time_of_impact),
+        Some(_) => None,
+        // The ray went through a gap in the rigid body, e.g. between the legs of
+        // a table, so nothing is in the way.
+        None => Some(dist),
+    }
+}
+
+/// Finds the prop the actor is targeting, as configured by
+/// [`AvianPickupActor::targeting`].
+/// Strategies looking straight ahead search up to `trace_length`,
+/// strategies looking around the actor search up to `nearby_length`.
+/// Props not passing [`AvianPickupActor::rigid_body_filter`] and props the
+/// actor is already holding are ignored.
+pub(crate) fn find_prop(
+    spatial_query: &SpatialQuery,
+    origin: Transform,
+    trace_length: f32,
+    nearby_length: f32,
+    config: &AvianPickupActor,
+    held_props: &AvianPickupActorHeldProps,
+    custom: Option<&CustomPropTargeting>,
+    q_collider: &Query<PropSearchData>,
+    rigid_body_filter: &RigidBodyFilterParams,
+) -> Option<Prop> {
+    let trace = || {
+        find_prop_in_trace(
+            spatial_query,
+            origin,
+            trace_length,
+            config,
+            held_props,
+            q_collider,
+            rigid_body_filter,
+        )
+    };
+    let cone = || {
+        find_prop_in_cone(
+            spatial_query,
+            origin,
+            nearby_length,
+            config,
+            held_props,
+            q_collider,
+            rigid_body_filter,
+        )
+    };
+    match config.targeting {
+        AvianPickupPropTargeting::Trace => trace(),
+        AvianPickupPropTargeting::Cone => cone(),
+        AvianPickupPropTargeting::TraceThenCone => trace().or_else(cone),
+        AvianPickupPropTargeting::NearestInSphere => find_prop_in_sphere(
+            spatial_query,
+            origin,
+            nearby_length,
+            config,
+            held_props,
+            q_collider,
+            rigid_body_filter,
+            |distance, _alignment| distance,
+        ),
+        AvianPickupPropTargeting::ClosestToCrosshair => find_prop_in_sphere(
+            spatial_query,
+            origin,
+            trace_length,
+            config,
+            held_props,
+            q_collider,
+            rigid_body_filter,
+            |_distance, alignment| {
+                if alignment > config.interaction_cone {
+                    -alignment
+                } else {
+                    Scalar::INFINITY
+                }
+            },
+        ),
+        AvianPickupPropTargeting::Custom => {
+            let Some(custom) = custom else {
+                error!("Actor uses `AvianPickupPropTargeting::Custom`, but has no `CustomPropTargeting`. Ignoring.");
+                return None;
+            };
+            let entity = (custom.0)(spatial_query, origin, trace_length, config)?;
+            if !rigid_body_filter.allows_collider(&config.rigid_body_filter, entity)
+                || is_held_collider(q_collider, held_props, entity)
+            {
+                return None;
+            }
+            let Some(center) = rigid_body_of(q_collider, entity)
+                .and_then(|rigid_body| center_of(q_collider, rigid_body))
+            else {
+                error!(
+                    "`CustomPropTargeting` returned an entity that is not a collider. Ignoring."
+                );
+                return None;
+            };
+            Some(Prop {
+                entity,
+                toi: center.distance(origin.translation),
+            })
+        }
+    }
+}
+
+/// Looks at all props within `radius` that are not occluded and returns the
+/// one with the lowest score. The score is calculated from the distance to the
+/// prop's center of mass and the dot product of the actor's forward direction
+/// and the direction to it. A score of infinity means that the prop is not
+/// eligible.
+fn find_prop_in_sphere(
+    spatial_query: &SpatialQuery,
+    origin: Transform,
+    radius: f32,
+    config: &AvianPickupActor,
+    held_props: &AvianPickupActorHeldProps,
+    q_collider: &Query<PropSearchData>,
+    rigid_body_filter: &RigidBodyFilterParams,
+    score: impl Fn(Scalar, Scalar) -> Scalar,
+) -> Option<Prop> {
+    let colliders = spatial_query.shape_intersections(
+        &sphere_collider(radius),
+        origin.translation.to_vector(),
+        to_rotation_value(origin.rotation),
+        &config.prop_filter,
+    );
+    let forward = actor_forward(origin.rotation);
+    let mut best_score = Scalar::INFINITY;
+    let mut candidate = None;
+    for hit in group_by_rigid_body(colliders, config, held_props, q_collider, rigid_body_filter) {
+        let Ok((los, dist)) = Dir3::new_and_length(hit.center - origin.translation) else {
+            continue;
+        };
+        let prop_score = score(dist, los.dot(*forward));
+        if prop_score >= best_score {
+            continue;
+        }
+        let Some(toi) = unoccluded_time_of_impact(
+            spatial_query,
+            origin.translation,
+            los,
+            dist,
+            config,
+            held_props,
+            hit.rigid_body,
+            q_collider,
+            rigid_body_filter,
+        ) else {
+            continue;
+        };
+        best_score = prop_score;
+        candidate.replace(Prop {
+            entity: hit.collider,
+            toi,
+        });
+    }
+    candidate
+}
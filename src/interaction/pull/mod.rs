@@ -1,105 +1,275 @@
+use bevy::ecs::system::EntityCommand as _;
+
 use crate::{
-    math::GetBestGlobalTransform,
+    interaction::{Holders, RigidBodyFilterParams},
+    math::{GetBestGlobalTransform, ToVec3 as _, ToVector as _},
+    permission::with_permission,
     prelude::*,
     verb::{Pulling, SetVerb, Verb},
 };
 
+mod apply_pull;
+mod arc;
 mod can_pull;
+mod candidate;
 mod find_in_cone;
 mod find_in_trace;
+mod find_prop;
+mod steal;
+mod vacuum;
 
-use self::{can_pull::*, find_in_cone::*, find_in_trace::*};
+pub(crate) use self::find_prop::{find_prop, PropSearchData};
+use self::{apply_pull::*, can_pull::*, candidate::*, steal::*, vacuum::*};
+use self::{find_in_cone::find_prop_in_cone, find_in_trace::find_prop_in_trace};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(PhysicsSchedule, find_object.in_set(HandleVerbSystem::Pull))
-        .add_systems(
-            PhysicsSchedule,
-            flush_pulling_state.in_set(AvianPickupSystem::ResetIdle),
-        );
+    app.add_systems(
+        PhysicsSchedule,
+        (find_object, vacuum, clear_pull_forces)
+            .chain()
+            .in_set(HandleVerbSystem::Pull),
+    )
+    .add_systems(
+        PhysicsSchedule,
+        flush_pulling_state.in_set(AvianPickupSystem::ResetIdle),
+    )
+    .add_systems(
+        PhysicsSchedule,
+        update_candidates.in_set(AvianPickupSystem::UpdateCandidate),
+    );
 }
 
 /// Inspired by [`CWeaponPhysCannon::FindObject`](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2497)
 fn find_object(
     mut commands: Commands,
-    spatial_query: SpatialQuery,
+    pull_params: PullParams,
     mut q_actor: Query<
         (
             Entity,
             &AvianPickupActor,
-            &mut AvianPickupActorState,
             &mut Cooldown,
+            &AvianPickupActorHeldProps,
+            Option<&CustomPropTargeting>,
         ),
         With<Pulling>,
     >,
     q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
-    q_collider_parent: Query<&ColliderParent>,
-    mut q_rigid_body: Query<(
-        &RigidBody,
-        &Mass,
-        &mut ExternalImpulse,
-        &Position,
-        Has<HeldProp>,
-    )>,
-    q_collider: Query<&Position>,
+    q_rigid_body: Query<
+        (
+            &RigidBody,
+            &Mass,
+            &LinearVelocity,
+            Option<&GravityScale>,
+            &Position,
+            Has<HeldProp>,
+            Has<CooperativeHold>,
+            Has<DynamicWhileHeld>,
+            Option<&Holders>,
+            Option<&NonPickupMass>,
+        ),
+        (
+            With<ExternalImpulse>,
+            With<ExternalForce>,
+            Without<AbsorbedProp>,
+        ),
+    >,
+    q_collider: Query<PropSearchData>,
+    rigid_body_filter: RigidBodyFilterParams,
+    q_holder: Query<&AvianPickupActor>,
 ) {
-    for (actor, config, mut state, mut cooldown) in q_actor.iter_mut() {
+    let spatial_query = &pull_params.spatial_query;
+    for (actor, config, mut cooldown, held_props, custom_targeting) in q_actor.iter_mut() {
+        if config.pull.area.is_some() {
+            // Handled by `vacuum`.
+            continue;
+        }
         let actor_transform = q_actor_transform.get_best_global_transform(actor);
-        let prop = find_prop_in_trace(&spatial_query, actor_transform, config)
-            .or_else(|| find_prop_in_cone(&spatial_query, actor_transform, config, &q_collider));
+        // Fun fact: Valve lies to you and actually multiplies the trace length by 4
+        // at this point.
+        let trace_length = config.interaction_distance;
+        // Valve uses the trace length for the cone as well, but imo using the hold
+        // distance makes more sense, as the raw trace length is what is also used for
+        // the hold check in the 2013 code. (Reminder that the actual trace is done
+        // with 4 times the configured trace length, eek)
+        let cone_length = config.hold.distance_to_allow_holding;
+        let prop = find_prop(
+            spatial_query,
+            actor_transform,
+            trace_length,
+            cone_length,
+            config,
+            held_props,
+            custom_targeting,
+            &q_collider,
+            &rigid_body_filter,
+        );
 
         let Some(prop) = prop else {
             continue;
         };
 
-        let Ok(rigid_body_entity) = q_collider_parent.get(prop.entity) else {
+        let Ok(rigid_body_entity) = pull_params.q_collider_parent.get(prop.entity) else {
             error!("Collider entity was deleted or in an invalid state. Ignoring.");
             continue;
         };
         let rigid_body_entity = rigid_body_entity.get();
 
-        let Ok((&rigid_body, &mass, mut impulse, prop_position, is_already_being_held)) =
-            q_rigid_body.get_mut(rigid_body_entity)
+        let Ok((
+            &rigid_body,
+            &mass,
+            velocity,
+            gravity_scale,
+            prop_position,
+            is_already_being_held,
+            is_cooperative,
+            dynamic_while_held,
+            holders,
+            non_pickup_mass,
+        )) = q_rigid_body.get(rigid_body_entity)
         else {
             // These components might not be present on non-dynamic rigid bodies
             continue;
         };
 
-        if is_already_being_held || !can_pull(rigid_body, mass, config) {
+        let is_held_by_others = is_already_being_held && !held_props.contains(rigid_body_entity);
+        let attempt = |kind| PickupAttempt {
+            actor,
+            prop: rigid_body_entity,
+            kind,
+        };
+        if is_held_by_others && !is_cooperative && config.pull.can_steal {
+            // The prop is lighter than usual while being held, so use its real mass.
+            let mass = non_pickup_mass.map(|mass| Mass(mass.0)).unwrap_or(mass);
+            let Some(holders) = holders else {
+                continue;
+            };
+            if can_pull(rigid_body, dynamic_while_held, mass, config)
+                && can_steal(config, holders, &q_holder)
+            {
+                let holders: Vec<_> = holders.holders.iter().map(|holder| holder.actor).collect();
+                let prop = rigid_body_entity;
+                with_permission(
+                    &mut commands,
+                    attempt(PickupAttemptKind::Pull),
+                    move |world| {
+                        for holder in holders {
+                            SetVerb::new(Verb::Drop { prop, forced: true }).apply(holder, world);
+                            world.send_event(PropStolen {
+                                prop,
+                                from: holder,
+                                to: actor,
+                            });
+                        }
+                    },
+                );
+            }
+            // The prop is free to be pulled on the next try.
+            cooldown.pull(&config.cooldown);
+            commands.entity(actor).add(SetVerb::new(None));
+            continue;
+        }
+
+        // Cooperative props can be grabbed even when they are already held or too heavy
+        // for us alone, but only pulled if neither is the case.
+        let can_join = is_cooperative
+            && can_become_dynamic(rigid_body, dynamic_while_held)
+            && !held_props.contains(rigid_body_entity);
+        let is_pullable =
+            !is_already_being_held && can_pull(rigid_body, dynamic_while_held, mass, config);
+        if !is_pullable && !can_join {
             continue;
         }
 
-        let can_hold = prop.toi <= config.interaction_distance;
-        if can_hold {
-            cooldown.hold();
-            commands
-                .entity(actor)
-                .add(SetVerb::new(Verb::Hold(rigid_body_entity)));
-        } else {
-            let direction = (actor_transform.translation - prop_position.0).normalize_or_zero();
-            let mass_adjustment = adjust_impulse_for_mass(mass);
-            let pull_impulse = direction * config.pull.impulse * mass_adjustment;
-            cooldown.pull();
-            impulse.apply_impulse(pull_impulse);
-            if !matches!(state.as_ref(), AvianPickupActorState::Pulling(..)) {
-                *state = AvianPickupActorState::Pulling(rigid_body_entity);
-            }
-            commands.entity(actor).add(SetVerb::new(None));
+        if prop.toi <= config.interaction_distance {
+            let cooldown_config = config.cooldown.clone();
+            with_permission(
+                &mut commands,
+                attempt(PickupAttemptKind::Hold),
+                move |world| {
+                    if let Some(mut cooldown) = world.get_mut::<Cooldown>(actor) {
+                        cooldown.hold(&cooldown_config);
+                    }
+                    SetVerb::new(Verb::Hold(rigid_body_entity)).apply(actor, world);
+                },
+            );
+        } else if is_pullable && rigid_body == RigidBody::Dynamic {
+            // Non-dynamic props would not react to the impulse anyways.
+            let pull = pull_params.pull(
+                config,
+                actor_transform.translation,
+                PulledProp {
+                    entity: rigid_body_entity,
+                    position: prop_position.0.to_vec3(),
+                    mass,
+                    velocity: velocity.0.to_vec3(),
+                    gravity_scale,
+                },
+            );
+            pull_with_permission(
+                &mut commands,
+                attempt(PickupAttemptKind::Pull),
+                pull,
+                config.cooldown.clone(),
+            );
         }
     }
 }
 
-/// Taken from [this snippet](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2607-L2610)
-fn adjust_impulse_for_mass(mass: Mass) -> f32 {
-    if mass.0 < 50.0 {
-        (mass.0 + 0.5) * (1.0 / 50.0)
-    } else {
-        1.0
+/// Applies the pull once the attempt is allowed and updates the actor
+/// accordingly.
+fn pull_with_permission(
+    commands: &mut Commands,
+    attempt: PickupAttempt,
+    pull: Pull,
+    cooldown_config: AvianPickupActorCooldownConfig,
+) {
+    let PickupAttempt { actor, prop, .. } = attempt;
+    with_permission(commands, attempt, move |world| {
+        pull.apply(world, prop);
+        // When pulling in addition to already held props, we stay in the `Holding` state.
+        if let Some(mut state) = world.get_mut::<AvianPickupActorState>(actor) {
+            if state.is_idle() {
+                *state = AvianPickupActorState::Pulling(prop);
+            }
+        }
+        if let Pull::Impulse(..) = pull {
+            if let Some(mut cooldown) = world.get_mut::<Cooldown>(actor) {
+                cooldown.pull(&cooldown_config);
+            }
+            SetVerb::new(None).apply(actor, world);
+        }
+        // Otherwise, we stay in `Pulling` for as long as the input is held,
+        // so that we keep pulling every physics step.
+    });
+}
+
+/// Lives on props pulled with [`AvianPickupPullMode::Force`] so that the force
+/// can be cleared once they are no longer pulled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+struct PullForce {
+    /// Whether the force was set during this physics step.
+    applied: bool,
+}
+
+/// Clears the force of props that were pulled with
+/// [`AvianPickupPullMode::Force`] during the last step, but not this one.
+fn clear_pull_forces(
+    mut commands: Commands,
+    mut q_prop: Query<(Entity, &mut PullForce, &mut ExternalForce)>,
+) {
+    for (prop, mut pull_force, mut force) in q_prop.iter_mut() {
+        if pull_force.applied {
+            pull_force.applied = false;
+        } else {
+            force.clear();
+            commands.entity(prop).remove::<PullForce>();
+        }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 
-struct Prop {
+pub(crate) struct Prop {
     pub entity: Entity,
     pub toi: f32,
 }
@@ -125,3 +295,66 @@ fn flush_pulling_state(mut q_state: Query<(Mut<AvianPickupActorState>, Has<Pulli
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::{time::TimeUpdateStrategy, transform::TransformPlugin};
+
+    use super::*;
+    use crate::math::{actor_forward, sphere_collider};
+
+    #[test]
+    fn picks_up_second_prop_while_holding_one() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            PhysicsPlugins::default(),
+            AvianPickupPlugin,
+        ))
+        .init_resource::<Assets<Mesh>>()
+        .insert_resource(Gravity(default()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1.0 / 60.0,
+        )));
+
+        let mut config = AvianPickupActor::default();
+        config.hold.max_held_props = 2;
+        let actor = app
+            .world_mut()
+            .spawn((TransformBundle::default(), config))
+            .id();
+        let forward = actor_forward(Quat::IDENTITY);
+        let mut spawn_prop = |distance: f32| {
+            app.world_mut()
+                .spawn((
+                    TransformBundle::from_transform(Transform::from_translation(
+                        forward * distance,
+                    )),
+                    RigidBody::Dynamic,
+                    sphere_collider(0.05),
+                ))
+                .id()
+        };
+        // The first prop ends up held right in front of the second one.
+        let near = spawn_prop(0.8);
+        let far = spawn_prop(1.4);
+
+        for _ in 0..180 {
+            app.world_mut().send_event(AvianPickupInput {
+                actor,
+                action: AvianPickupAction::Pull,
+            });
+            app.update();
+        }
+
+        let held_props = app.world().get::<AvianPickupActorHeldProps>(actor).unwrap();
+        assert!(held_props.contains(near));
+        assert!(held_props.contains(far));
+    }
+}
@@ -0,0 +1,71 @@
+use avian::math::Scalar;
+
+use crate::{interaction::Holders, prelude::*};
+
+/// Whether the actor's grip is stronger than the combined grip of everyone
+/// holding the prop.
+pub(super) fn can_steal(
+    config: &AvianPickupActor,
+    holders: &Holders,
+    q_holder: &Query<&AvianPickupActor>,
+) -> bool {
+    let defending_grip: Scalar = holders
+        .holders
+        .iter()
+        .filter_map(|holder| q_holder.get(holder.actor).ok())
+        .map(|holder_config| holder_config.hold.grip_strength)
+        .sum();
+    config.pull.can_steal && config.hold.grip_strength > defending_grip
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+    use crate::interaction::Holder;
+
+    fn actor(can_steal: bool, grip_strength: Scalar) -> AvianPickupActor {
+        let mut config = AvianPickupActor::default();
+        config.pull.can_steal = can_steal;
+        config.hold.grip_strength = grip_strength;
+        config
+    }
+
+    fn holders(actors: &[Entity]) -> Holders {
+        Holders {
+            holders: actors
+                .iter()
+                .map(|&actor| Holder {
+                    actor,
+                    pre_pickup_rotation: Quat::IDENTITY,
+                    target_position: Vec3::ZERO,
+                    target_rotation: Quat::IDENTITY,
+                })
+                .collect(),
+            pickup_mass: 1.0,
+            lifted: true,
+        }
+    }
+
+    #[test]
+    fn stealing_needs_a_stronger_grip_than_all_holders() {
+        let mut world = World::new();
+        let weak = world.spawn(actor(false, 1.0)).id();
+        let strong = world.spawn(actor(false, 2.0)).id();
+        let mut state = SystemState::<Query<&AvianPickupActor>>::new(&mut world);
+        let q_holder = state.get(&world);
+
+        let thief = actor(true, 2.5);
+        assert!(can_steal(&thief, &holders(&[weak]), &q_holder));
+        assert!(can_steal(&thief, &holders(&[strong]), &q_holder));
+        // The holders' grips add up.
+        assert!(!can_steal(&thief, &holders(&[weak, strong]), &q_holder));
+        // A tie is not enough.
+        let tied = actor(true, 2.0);
+        assert!(!can_steal(&tied, &holders(&[strong]), &q_holder));
+        // Actors without `can_steal` never steal.
+        let not_a_thief = actor(false, 10.0);
+        assert!(!can_steal(&not_a_thief, &holders(&[weak]), &q_holder));
+    }
+}
@@ -0,0 +1,159 @@
+use avian::math::Scalar;
+
+use super::{apply_pull::*, can_pull::*, pull_with_permission};
+use crate::{
+    interaction::RigidBodyFilterParams,
+    math::{
+        actor_forward, sphere_collider, to_dir, to_rotation_value, GetBestGlobalTransform,
+        ToVec3 as _, ToVector as _,
+    },
+    permission::with_permission,
+    prelude::*,
+    verb::Pulling,
+};
+
+/// Pulls all eligible props around the actor at once, as configured by
+/// [`AvianPickupActorPullConfig::area`].
+pub(super) fn vacuum(
+    mut commands: Commands,
+    pull_params: PullParams,
+    rigid_body_filter: RigidBodyFilterParams,
+    q_actor: Query<(Entity, &AvianPickupActor), With<Pulling>>,
+    q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
+    q_rigid_body: Query<
+        (
+            &RigidBody,
+            &Mass,
+            &LinearVelocity,
+            Option<&GravityScale>,
+            &Position,
+            Has<DynamicWhileHeld>,
+        ),
+        (
+            With<ExternalImpulse>,
+            With<ExternalForce>,
+            Without<HeldProp>,
+            Without<AbsorbedProp>,
+        ),
+    >,
+) {
+    for (actor, config) in q_actor.iter() {
+        let Some(area) = &config.pull.area else {
+            continue;
+        };
+        let actor_transform = q_actor_transform.get_best_global_transform(actor);
+        let origin = actor_transform.translation;
+        let forward = actor_forward(actor_transform.rotation);
+        let colliders = pull_params.spatial_query.shape_intersections(
+            &sphere_collider(area.radius),
+            origin.to_vector(),
+            to_rotation_value(actor_transform.rotation),
+            &config.prop_filter,
+        );
+
+        let mut candidates: Vec<(Entity, Scalar)> = Vec::new();
+        for collider in colliders {
+            let Ok(rigid_body_entity) = pull_params.q_collider_parent.get(collider) else {
+                continue;
+            };
+            let prop = rigid_body_entity.get();
+            if candidates.iter().any(|&(candidate, _)| candidate == prop)
+                || !rigid_body_filter.allows(&config.rigid_body_filter, prop)
+            {
+                continue;
+            }
+            let Ok((&rigid_body, &mass, .., position, dynamic_while_held)) = q_rigid_body.get(prop)
+            else {
+                // Held, absorbed, or missing components on a non-dynamic rigid body
+                continue;
+            };
+            // Non-dynamic props would not react to the impulse anyways.
+            if rigid_body != RigidBody::Dynamic
+                || !can_pull(rigid_body, dynamic_while_held, mass, config)
+            {
+                continue;
+            }
+            let to_prop = position.0.to_vec3() - origin;
+            let distance = to_prop.length();
+            if let Ok(direction) = Dir3::new(to_prop) {
+                if area
+                    .cone
+                    .is_some_and(|cone| direction.dot(*forward) <= cone)
+                {
+                    continue;
+                }
+                if is_occluded(&pull_params, config, origin, direction, distance, prop) {
+                    continue;
+                }
+            }
+            candidates.push((prop, distance));
+        }
+        candidates.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        candidates.truncate(area.max_props);
+
+        for (prop, distance) in candidates {
+            if distance <= config.hold.distance_to_allow_holding {
+                let attempt = PickupAttempt {
+                    actor,
+                    prop,
+                    kind: PickupAttemptKind::Hold,
+                };
+                with_permission(&mut commands, attempt, move |world| {
+                    world.entity_mut(prop).insert(AbsorbedProp);
+                    if let Some(mut absorbed_props) =
+                        world.get_mut::<AvianPickupActorAbsorbedProps>(actor)
+                    {
+                        absorbed_props.0.push(prop);
+                    }
+                    world.send_event(PropAbsorbed { actor, prop });
+                });
+                continue;
+            }
+            // Safety: we just checked that the prop matches the query.
+            let (_, &mass, velocity, gravity_scale, position, _) = q_rigid_body.get(prop).unwrap();
+            let pull = pull_params.pull(
+                config,
+                origin,
+                PulledProp {
+                    entity: prop,
+                    position: position.0.to_vec3(),
+                    mass,
+                    velocity: velocity.0.to_vec3(),
+                    gravity_scale,
+                },
+            );
+            let attempt = PickupAttempt {
+                actor,
+                prop,
+                kind: PickupAttemptKind::Pull,
+            };
+            pull_with_permission(&mut commands, attempt, pull, config.cooldown.clone());
+        }
+    }
+}
+
+/// Whether there is terrain between the actor and the prop.
+fn is_occluded(
+    pull_params: &PullParams,
+    config: &AvianPickupActor,
+    origin: Vec3,
+    direction: Dir3,
+    distance: Scalar,
+    prop: Entity,
+) -> bool {
+    let hit = pull_params.spatial_query.cast_ray_predicate(
+        origin.to_vector(),
+        to_dir(direction),
+        distance,
+        true,
+        &config.obstacle_filter,
+        // The prop's own colliders are not terrain.
+        &|entity| {
+            pull_params
+                .q_collider_parent
+                .get(entity)
+                .map_or(true, |parent| parent.get() != prop)
+        },
+    );
+    hit.is_some()
+}
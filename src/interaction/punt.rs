@@ -0,0 +1,114 @@
+use avian::math::Scalar;
+
+use super::{
+    pull::{find_prop, PropSearchData},
+    throw::remap_through_spline,
+};
+use crate::{
+    interaction::RigidBodyFilterParams,
+    math::{actor_forward, GetBestGlobalTransform, ToVector as _},
+    permission::check_permission,
+    prelude::*,
+    verb::Punting,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(PhysicsSchedule, punt.in_set(HandleVerbSystem::Punt));
+}
+
+/// Inspired by `CWeaponPhysCannon::PrimaryAttack` and
+/// `CWeaponPhysCannon::PuntVPhysics`
+fn punt(
+    mut commands: Commands,
+    spatial_query: SpatialQuery,
+    q_actor: Query<
+        (
+            Entity,
+            &AvianPickupActor,
+            &AvianPickupActorHeldProps,
+            Option<&CustomPropTargeting>,
+        ),
+        With<Punting>,
+    >,
+    q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
+    q_collider_parent: Query<&ColliderParent>,
+    q_rigid_body: Query<(&RigidBody, &Mass, Has<HeldProp>), With<ExternalImpulse>>,
+    q_collider: Query<PropSearchData>,
+    rigid_body_filter: RigidBodyFilterParams,
+) {
+    for (actor, config, held_props, custom_targeting) in q_actor.iter() {
+        commands.entity(actor).remove::<Punting>();
+        let actor_transform = q_actor_transform.get_best_global_transform(actor);
+        let distance = config.punt.distance;
+        let prop = find_prop(
+            &spatial_query,
+            actor_transform,
+            distance,
+            distance,
+            config,
+            held_props,
+            custom_targeting,
+            &q_collider,
+            &rigid_body_filter,
+        );
+        let Some(prop) = prop else {
+            continue;
+        };
+
+        let Ok(rigid_body_entity) = q_collider_parent.get(prop.entity) else {
+            error!("Collider entity was deleted or in an invalid state. Ignoring.");
+            continue;
+        };
+        let rigid_body_entity = rigid_body_entity.get();
+        let Ok((&rigid_body, &mass, is_held)) = q_rigid_body.get(rigid_body_entity) else {
+            // These components might not be present on non-dynamic rigid bodies
+            continue;
+        };
+        // Non-dynamic props would not react to the impulse anyways.
+        // Held props are the business of their holders.
+        if rigid_body != RigidBody::Dynamic || is_held {
+            continue;
+        }
+        let Some(mass_adjustment) = adjust_impulse_for_mass(config, mass) else {
+            continue;
+        };
+        let forward = actor_forward(actor_transform.rotation);
+        let punt_impulse = forward * config.punt.impulse * mass_adjustment;
+        let attempt = PickupAttempt {
+            actor,
+            prop: rigid_body_entity,
+            kind: PickupAttemptKind::Punt,
+        };
+        let cooldown_config = config.cooldown.clone();
+        // Asking the `AvianPickupVeto` needs the whole world.
+        commands.add(move |world: &mut World| {
+            if !check_permission(world, attempt) {
+                return;
+            }
+            if let Some(mut impulse) = world.get_mut::<ExternalImpulse>(attempt.prop) {
+                impulse.apply_impulse(punt_impulse.to_vector());
+            }
+            if let Some(mut cooldown) = world.get_mut::<Cooldown>(actor) {
+                cooldown.punt(&cooldown_config);
+            }
+            world.send_event(PropPunted {
+                actor,
+                prop: attempt.prop,
+            });
+        });
+    }
+}
+
+/// Returns `None` if the prop is too heavy to be punted.
+fn adjust_impulse_for_mass(config: &AvianPickupActor, mass: Mass) -> Option<Scalar> {
+    let falloff = &config.punt.mass_falloff;
+    let (light_mass, max_mass) = (*falloff.start(), *falloff.end());
+    if mass.0 >= max_mass {
+        None
+    } else if mass.0 < light_mass {
+        // Same as `AvianPickupPullMassAdjustment::HalfLife2` when `light_mass` is 50 kg.
+        Some(((mass.0 + 0.5) / light_mass).min(1.0))
+    } else {
+        Some(remap_through_spline(mass.0, falloff.clone(), 1.0..=0.0))
+    }
+}
@@ -1,71 +1,299 @@
 use std::ops::RangeInclusive;
 
-use avian3d::math::Scalar;
+use avian::math::Scalar;
 use rand::Rng;
 
-use crate::{math::GetBestGlobalTransform, prelude::*, rng::RngSource, verb::Throwing};
+use crate::{
+    math::{
+        actor_forward, to_angular_value, GetBestGlobalTransform, GetRigidBodyVelocity as _,
+        ToVec3 as _, ToVector as _,
+    },
+    prelude::*,
+    rng::RngSource,
+    verb::{ChargingThrow, PendingThrow, Throwing},
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(PhysicsSchedule, throw.in_set(HandleVerbSystem::Throw));
+    app.add_systems(
+        PhysicsSchedule,
+        (throw, charge_throw)
+            .chain()
+            .in_set(HandleVerbSystem::Throw),
+    );
 }
 
-/// Note: in constrast to the physcannon, we do not allow punting when not
-/// holding any prop. I think this should be handled by the user.
+/// Note: in constrast to the physcannon, throwing does not punt props when not
+/// holding any. That is handled separately by [`AvianPickupAction::Punt`].
 fn throw(
     mut commands: Commands,
     mut q_actor: Query<(
         Entity,
         &AvianPickupActor,
-        &mut AvianPickupActorState,
         &mut Cooldown,
         &Throwing,
+        Option<&ChargingThrow>,
     )>,
     q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
-    mut q_prop: Query<(
-        &mut LinearVelocity,
-        &mut AngularVelocity,
-        &Mass,
-        Option<&ThrownLinearSpeedOverride>,
-        Option<&ThrownAngularSpeedOverride>,
+    // The target and the actor might be moving, so we need to read their
+    // velocities while also writing the prop's velocity.
+    mut q_prop_and_target: ParamSet<(
+        Query<(
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            &Mass,
+            &RigidBody,
+            &Position,
+            Option<&ThrownLinearSpeedOverride>,
+            Option<&ThrownAngularSpeedOverride>,
+            Option<&ThrownSpinAxisOverride>,
+            Option<&GravityScale>,
+        )>,
+        Query<(&GlobalTransform, Option<&LinearVelocity>)>,
+        Query<(Option<&LinearVelocity>, Option<&Parent>)>,
     )>,
     mut w_throw_event: EventWriter<PropThrown>,
+    mut w_drop_event: EventWriter<PropDropped>,
     mut rng: ResMut<RngSource>,
+    gravity: Res<Gravity>,
 ) {
-    for (actor, config, mut states, mut cooldown, throw) in q_actor.iter_mut() {
-        let prop = throw.0;
-        commands.entity(actor).remove::<Throwing>();
+    let gravity = gravity.0.to_vec3();
+    for (actor, config, mut cooldown, throwing, charging) in q_actor.iter_mut() {
+        commands.entity(actor).remove::<(Throwing, ChargingThrow)>();
         let actor_transform = q_actor_transform.get_best_global_transform(actor);
-        // Safety: All props are rigid bodies, which are guaranteed to have a
-        // `LinearVelocity`, `AngularVelocity`, and `Mass`.
-        let Ok((mut velocity, mut angvel, mass, lin_speed_override, ang_speed_override)) =
-            q_prop.get_mut(prop)
-        else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+        let inherited_velocity =
+            q_prop_and_target.p2().get_rigid_body_velocity(actor) * config.velocity_inheritance;
+        for &PendingThrow { prop, target } in throwing.0.iter() {
+            let target = target.and_then(|target| target_motion(target, &q_prop_and_target.p1()));
+            let mut q_prop = q_prop_and_target.p0();
+            // Safety: All props are rigid bodies, which are guaranteed to have a
+            // `LinearVelocity`, `AngularVelocity`, and `Mass`.
+            let Ok((
+                mut velocity,
+                mut angvel,
+                mass,
+                rigid_body,
+                position,
+                lin_speed_override,
+                ang_speed_override,
+                spin_axis_override,
+                gravity_scale,
+            )) = q_prop.get_mut(prop)
+            else {
+                error!("Prop entity was deleted or in an invalid state. Ignoring.");
+                continue;
+            };
+            if *rigid_body != RigidBody::Dynamic {
+                // The prop went back to being kinematic or static and stays where
+                // it was released, see `DynamicWhileHeld`. That's a drop, not a throw.
+                cooldown.drop(&config.cooldown);
+                w_drop_event.send(PropDropped {
+                    actor,
+                    prop,
+                    forced: false,
+                });
+                continue;
+            }
+            w_throw_event.send(PropThrown { actor, prop });
+            cooldown.throw(&config.cooldown);
+            // The 2013 code now does a `continue` on
+            // `prop_dist_sq > config.interaction_distance * config.interaction_distance`
+            // but eh, that's fine. Better to respect players' input in such edge cases.
+
+            let charging = charging.filter(|charging| charging.prop == prop);
+            let lin_speed = launch_speed(config, *mass, lin_speed_override, charging);
+            let from = position.0.to_vec3();
+            let gravity_scale = gravity_scale.map_or(1.0, |scale| scale.0);
+            // Aim in the actor's frame of reference, so that the inherited velocity
+            // does not make us miss.
+            let aimed_velocity = target.and_then(|(target_position, target_velocity)| {
+                intercept_velocity(
+                    from,
+                    target_position,
+                    target_velocity - inherited_velocity,
+                    lin_speed,
+                    gravity,
+                    gravity_scale,
+                )
+            });
+            let launch_velocity = aimed_velocity
+                .unwrap_or_else(|| actor_forward(actor_transform.rotation) * lin_speed);
+            velocity.0 = (launch_velocity + inherited_velocity).to_vector();
+
+            let spin = spin_axis_override
+                .map(|axis| AvianPickupThrowSpin::Axis(axis.0))
+                .unwrap_or(config.throw.spin);
+            let ang_speed_range = &config.throw.angular_speed_range;
+            let spin_velocity = match spin {
+                AvianPickupThrowSpin::Random => {
+                    let rand_direction = random_unit_vector(rng.as_mut());
+                    let rand_magnitude = ang_speed_override
+                        .map(|s| s.0)
+                        .unwrap_or_else(|| rng.as_mut().gen_range(ang_speed_range.clone()));
+                    rand_direction * rand_magnitude
+                }
+                AvianPickupThrowSpin::Axis(axis) => {
+                    let direction = (actor_transform.rotation * axis).normalize_or_zero();
+                    let magnitude = ang_speed_override
+                        .map(|s| s.0)
+                        .unwrap_or(*ang_speed_range.end());
+                    direction * magnitude
+                }
+            };
+            angvel.0 = to_angular_value(spin_velocity);
+        }
+    }
+}
+
+/// Ticks the charge of throws that have not been released yet.
+fn charge_throw(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_actor: Query<(
+        Entity,
+        &AvianPickupActor,
+        &AvianPickupActorHeldProps,
+        &mut ChargingThrow,
+    )>,
+    mut w_charging_event: EventWriter<ThrowCharging>,
+) {
+    let dt = time.delta_seconds();
+    for (actor, config, held_props, mut charging) in q_actor.iter_mut() {
+        let prop = charging.prop;
+        if !held_props.contains(prop) {
+            // The prop was dropped or thrown in the meantime.
+            commands.entity(actor).remove::<ChargingThrow>();
             continue;
-        };
-        // The 2013 code now does a `continue` on
-        // `prop_dist_sq > config.interaction_distance * config.interaction_distance`
-        // but eh, that's fine. Better to respect players' input in such edge cases.
-
-        let lin_direction = actor_transform.forward();
-        let lin_speed = lin_speed_override
-            .map(|s| s.0)
-            .unwrap_or_else(|| calculate_launch_speed(config, *mass));
-        velocity.0 = lin_direction * lin_speed;
-
-        let rand_direction = random_unit_vector(rng.as_mut());
-        let rand_magnitude = ang_speed_override.map(|s| s.0).unwrap_or_else(|| {
-            rng.as_mut()
-                .gen_range(config.throw.angular_speed_range.clone())
+        }
+        charging.elapsed += dt;
+        w_charging_event.send(ThrowCharging {
+            actor,
+            prop,
+            fraction: charge_fraction(config, charging.elapsed),
         });
-        angvel.0 = rand_direction * rand_magnitude;
+    }
+}
+
+/// The position and velocity of the given target.
+pub(crate) fn target_motion(
+    target: AvianPickupThrowTarget,
+    q_target: &Query<(&GlobalTransform, Option<&LinearVelocity>)>,
+) -> Option<(Vec3, Vec3)> {
+    match target {
+        AvianPickupThrowTarget::Point(point) => Some((point, Vec3::ZERO)),
+        AvianPickupThrowTarget::Entity(entity) => {
+            let Ok((transform, velocity)) = q_target.get(entity) else {
+                error!("Throw target entity was deleted or in an invalid state. Throwing straight ahead instead.");
+                return None;
+            };
+            let velocity = velocity.map(|v| v.0.to_vec3()).unwrap_or_default();
+            Some((transform.translation(), velocity))
+        }
+    }
+}
 
-        *states = AvianPickupActorState::Idle;
-        w_throw_event.send(PropThrown { actor, prop });
-        cooldown.throw();
+/// Solves for the velocity with which a prop thrown at `speed` from `from`
+/// lands on `to`, taking `gravity` scaled by the prop's `gravity_scale` into
+/// account. Of the two possible arcs, the flatter one is used. If `to` is out
+/// of reach, returns the arc that gets closest to it.\
+/// Returns the velocity and the time of flight, or `None` if the prop cannot
+/// be thrown at all.
+fn ballistic_velocity(
+    from: Vec3,
+    to: Vec3,
+    speed: Scalar,
+    gravity: Vec3,
+    gravity_scale: Scalar,
+) -> Option<(Vec3, Scalar)> {
+    if speed <= 0.0 {
+        return None;
+    }
+    let gravity = gravity * gravity_scale;
+    let delta = to - from;
+    let g = gravity.length();
+    if g < Scalar::EPSILON {
+        let direction = delta.try_normalize()?;
+        return Some((direction * speed, delta.length() / speed));
     }
+    let up = -gravity / g;
+    let height = delta.dot(up);
+    let horizontal = delta - up * height;
+    let x = horizontal.length();
+    if x < 1e-4 {
+        // Straight up or down
+        let direction = if height >= 0.0 { up } else { -up };
+        return Some((direction * speed, height.abs() / speed));
+    }
+    let horizontal_direction = horizontal / x;
+    let v2 = speed * speed;
+    // Clamping to 0 gives the arc with the furthest reach when the target is
+    // out of range.
+    let discriminant = (v2 * v2 - g * (g * x * x + 2.0 * height * v2)).max(0.0);
+    let angle = ((v2 - discriminant.sqrt()) / (g * x)).atan();
+    let velocity = horizontal_direction * speed * angle.cos() + up * speed * angle.sin();
+    let time = x / (speed * angle.cos());
+    Some((velocity, time))
 }
 
+/// Like [`ballistic_velocity`], but leads a target moving with
+/// `target_velocity`.
+pub(crate) fn intercept_velocity(
+    from: Vec3,
+    target_position: Vec3,
+    target_velocity: Vec3,
+    speed: Scalar,
+    gravity: Vec3,
+    gravity_scale: Scalar,
+) -> Option<Vec3> {
+    let (mut velocity, mut time) =
+        ballistic_velocity(from, target_position, speed, gravity, gravity_scale)?;
+    // A few iterations are enough to converge for targets that are not
+    // faster than the prop.
+    for _ in 0..3 {
+        let predicted_position = target_position + target_velocity * time;
+        (velocity, time) =
+            ballistic_velocity(from, predicted_position, speed, gravity, gravity_scale)?;
+    }
+    Some(velocity)
+}
+
+/// The linear speed a prop will be thrown with.
+pub(crate) fn launch_speed(
+    config: &AvianPickupActor,
+    mass: Mass,
+    lin_speed_override: Option<&ThrownLinearSpeedOverride>,
+    charging: Option<&ChargingThrow>,
+) -> Scalar {
+    let lin_speed = lin_speed_override
+        .map(|s| s.0)
+        .unwrap_or_else(|| calculate_launch_speed(config, mass));
+    if let Some(charging) = charging {
+        let fraction = charge_fraction(config, charging.elapsed);
+        let min_speed = *config.throw.linear_speed_range.start();
+        min_speed.lerp(lin_speed, config.throw.charge_curve.sample(fraction))
+    } else {
+        lin_speed
+    }
+}
+
+fn charge_fraction(config: &AvianPickupActor, elapsed: Scalar) -> Scalar {
+    if config.throw.charge_duration <= 0.0 {
+        1.0
+    } else {
+        (elapsed / config.throw.charge_duration).clamp(0.0, 1.0)
+    }
+}
+
+/// In 2D, this is a rotation axis that makes the prop spin in a random direction.
+#[cfg(feature = "2d")]
+fn random_unit_vector(rng: &mut impl Rng) -> Vec3 {
+    if rng.gen_bool(0.5) {
+        Vec3::Z
+    } else {
+        Vec3::NEG_Z
+    }
+}
+
+#[cfg(feature = "3d")]
 fn random_unit_vector(rng: &mut impl Rng) -> Vec3 {
     Sphere::new(1.0).sample_boundary(rng)
 }
@@ -90,7 +318,7 @@ fn calculate_launch_speed(config: &AvianPickupActor, mass: Mass) -> Scalar {
 /// `domain` and `image` are mathematical terms.
 ///
 /// Corresponds to 2013's `SimpleSplineRemapValClamped`
-fn remap_through_spline(
+pub(super) fn remap_through_spline(
     val: Scalar,
     domain: RangeInclusive<Scalar>,
     image: RangeInclusive<Scalar>,
@@ -134,6 +362,45 @@ mod test {
         assert_eq!(remap(1000.), MINFORCE);
     }
 
+    #[test]
+    fn ballistic_velocity_hits_target() {
+        let gravity = Vec3::NEG_Y * 9.81;
+        let from = Vec3::new(0.0, 1.0, 0.0);
+        let to = Vec3::new(3.0, 0.5, -4.0);
+        for gravity_scale in [1.0, 0.5, 2.0] {
+            let (velocity, time) =
+                ballistic_velocity(from, to, 10.0, gravity, gravity_scale).unwrap();
+            assert!((velocity.length() - 10.0).abs() < 1e-3);
+            let gravity = gravity * gravity_scale;
+            let landing = from + velocity * time + 0.5 * gravity * time * time;
+            assert!(landing.distance(to) < 1e-3);
+        }
+    }
+
+    #[test]
+    fn charged_throw_lerps_launch_speed() {
+        let mut config = AvianPickupActor::default();
+        config.throw.linear_speed_range = 2.0..=10.0;
+        config.throw.charge_duration = 2.0;
+        config.throw.charge_curve = AvianPickupThrowChargeCurve::EaseIn;
+        let mass = Mass(1.0);
+        let lin_speed_override = ThrownLinearSpeedOverride(8.0);
+        let speed = |elapsed: Option<Scalar>| {
+            let charging = elapsed.map(|elapsed| ChargingThrow {
+                prop: Entity::PLACEHOLDER,
+                elapsed,
+            });
+            launch_speed(&config, mass, Some(&lin_speed_override), charging.as_ref())
+        };
+        assert_eq!(speed(None), 8.0);
+        assert_eq!(speed(Some(0.0)), 2.0);
+        // Half the charge time is a quarter of the speed gain with `EaseIn`.
+        assert_eq!(speed(Some(1.0)), 3.5);
+        assert_eq!(speed(Some(2.0)), 8.0);
+        // Charging longer than `charge_duration` does not go past full speed.
+        assert_eq!(speed(Some(5.0)), 8.0);
+    }
+
     #[test]
     fn is_random_unit_vector_actually_unit() {
         let mut rng = thread_rng();
@@ -2,15 +2,36 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../readme.md")]
 
-use avian3d::prelude::*;
+#[cfg(all(feature = "2d", feature = "3d"))]
+compile_error!(
+    "The `2d` and `3d` features of `avian_pickup` are mutually exclusive. \
+    To use `2d`, add `avian_pickup` with `default-features = false`."
+);
+#[cfg(not(any(feature = "2d", feature = "3d")))]
+compile_error!("Either the `2d` or the `3d` feature of `avian_pickup` must be enabled.");
+
+/// The Avian backend in use, i.e. `avian2d` with the `2d` feature and `avian3d`
+/// with the `3d` feature. Re-exported so that code can refer to it without
+/// caring about which one is enabled.
+#[cfg(feature = "2d")]
+pub extern crate avian2d as avian;
+/// The Avian backend in use, i.e. `avian2d` with the `2d` feature and `avian3d`
+/// with the `3d` feature. Re-exported so that code can refer to it without
+/// caring about which one is enabled.
+#[cfg(feature = "3d")]
+pub extern crate avian3d as avian;
+
+use avian::prelude::*;
 use bevy::prelude::*;
 
 pub mod actor;
-mod cooldown;
+pub mod cooldown;
 pub mod input;
 mod interaction;
 mod math;
 pub mod output;
+pub mod permission;
+pub mod preview;
 pub mod prop;
 mod rng;
 mod verb;
@@ -18,17 +39,16 @@ mod verb;
 pub use rng::*;
 /// Everything you need to get started with Avian Pickup.
 pub mod prelude {
-    pub(crate) use avian3d::prelude::*;
+    pub(crate) use avian::prelude::*;
     pub(crate) use bevy::prelude::*;
 
     pub(crate) use super::{
-        cooldown::prelude::*,
-        prop::{prelude::*, NonPickupMass},
+        prop::{prelude::*, NonPickupMass, NonPickupRigidBody},
         HandleVerbSystem,
     };
     pub use crate::{
-        actor::prelude::*, input::prelude::*, output::prelude::*, AvianPickupPlugin,
-        AvianPickupSystem,
+        actor::prelude::*, cooldown::prelude::*, input::prelude::*, output::prelude::*,
+        permission::prelude::*, preview::prelude::*, AvianPickupPlugin, AvianPickupSystem,
     };
 }
 
@@ -38,7 +58,7 @@ pub mod prelude {
 /// # Example
 ///
 /// ```no_run
-/// # use avian3d::prelude::*;
+/// # use avian_pickup::avian::prelude::*;
 /// # use avian_pickup::prelude::*;
 /// # use bevy::prelude::*;
 ///
@@ -69,6 +89,7 @@ impl Plugin for AvianPickupPlugin {
                 AvianPickupSystem::First,
                 AvianPickupSystem::HandleVerb,
                 AvianPickupSystem::ResetIdle,
+                AvianPickupSystem::UpdateCandidate,
                 AvianPickupSystem::TickTimers,
                 AvianPickupSystem::Last,
             )
@@ -82,6 +103,7 @@ impl Plugin for AvianPickupPlugin {
                 HandleVerbSystem::Hold,
                 HandleVerbSystem::Drop,
                 HandleVerbSystem::Throw,
+                HandleVerbSystem::Punt,
             )
                 .chain()
                 .in_set(AvianPickupSystem::HandleVerb),
@@ -94,6 +116,8 @@ impl Plugin for AvianPickupPlugin {
             interaction::plugin,
             cooldown::plugin,
             prop::plugin,
+            permission::plugin,
+            preview::plugin,
             verb::plugin,
             rng::plugin,
         ));
@@ -117,6 +141,9 @@ pub enum AvianPickupSystem {
     /// [`AvianPickupActorState::Idle`](crate::prelude::AvianPickupActorState::Idle)
     /// if needed
     ResetIdle,
+    /// Updates the [`PickupCandidate`](crate::prelude::PickupCandidate) of
+    /// actors that have one.
+    UpdateCandidate,
     /// Advances internal cooldown timers.
     TickTimers,
     /// Runs at the end of the [`AvianPickupSystem`]. Empty by default.
@@ -129,4 +156,5 @@ pub(crate) enum HandleVerbSystem {
     Hold,
     Drop,
     Throw,
+    Punt,
 }
@@ -1,8 +1,233 @@
-use avian3d::{prelude::*, sync::ancestor_marker::AncestorMarker};
+use std::ops::RangeInclusive;
+
+use avian::{
+    math::{Scalar, Vector},
+    prelude::*,
+    sync::ancestor_marker::AncestorMarker,
+};
 use bevy::prelude::*;
 
 pub(crate) const METERS_PER_INCH: f32 = 0.0254;
 
+// Internally, we do all our math in Bevy's 3D space, i.e. with `Vec3`s and `Quat`s.
+// In 2D, everything lives in the XY plane and only rotates around the Z axis.
+// The helpers below convert from and to whatever Avian uses at the API boundaries.
+
+/// The direction type used by Avian's spatial queries.
+#[cfg(feature = "2d")]
+pub(crate) type Dir = Dir2;
+/// The direction type used by Avian's spatial queries.
+#[cfg(feature = "3d")]
+pub(crate) type Dir = Dir3;
+
+/// The type Avian uses for rotations passed to shape queries.
+#[cfg(feature = "2d")]
+pub(crate) type RotationValue = Scalar;
+/// The type Avian uses for rotations passed to shape queries.
+#[cfg(feature = "3d")]
+pub(crate) type RotationValue = Quat;
+
+/// The type of the value inside [`AngularVelocity`].
+#[cfg(feature = "2d")]
+pub(crate) type AngularValue = Scalar;
+/// The type of the value inside [`AngularVelocity`].
+#[cfg(feature = "3d")]
+pub(crate) type AngularValue = Vec3;
+
+pub(crate) trait ToVector {
+    fn to_vector(self) -> Vector;
+}
+
+impl ToVector for Vec3 {
+    #[cfg(feature = "2d")]
+    fn to_vector(self) -> Vector {
+        self.truncate()
+    }
+
+    #[cfg(feature = "3d")]
+    fn to_vector(self) -> Vector {
+        self
+    }
+}
+
+pub(crate) trait ToVec3 {
+    fn to_vec3(self) -> Vec3;
+}
+
+#[cfg(feature = "2d")]
+impl ToVec3 for Vector {
+    fn to_vec3(self) -> Vec3 {
+        self.extend(0.0)
+    }
+}
+
+#[cfg(feature = "3d")]
+impl ToVec3 for Vector {
+    fn to_vec3(self) -> Vec3 {
+        self
+    }
+}
+
+pub(crate) trait ToQuat {
+    fn to_quat(&self) -> Quat;
+}
+
+impl ToQuat for Rotation {
+    #[cfg(feature = "2d")]
+    fn to_quat(&self) -> Quat {
+        Quat::from_rotation_z(self.as_radians())
+    }
+
+    #[cfg(feature = "3d")]
+    fn to_quat(&self) -> Quat {
+        self.0
+    }
+}
+
+#[cfg(feature = "2d")]
+pub(crate) fn to_dir(dir: Dir3) -> Dir {
+    Dir2::new(dir.truncate()).unwrap_or(Dir2::X)
+}
+
+#[cfg(feature = "3d")]
+pub(crate) fn to_dir(dir: Dir3) -> Dir {
+    dir
+}
+
+#[cfg(feature = "2d")]
+pub(crate) fn to_rotation_value(rotation: Quat) -> RotationValue {
+    rotation.to_euler(EulerRot::ZYX).0
+}
+
+#[cfg(feature = "3d")]
+pub(crate) fn to_rotation_value(rotation: Quat) -> RotationValue {
+    rotation
+}
+
+#[cfg(feature = "2d")]
+pub(crate) fn to_rotation(rotation: Quat) -> Rotation {
+    Rotation::radians(to_rotation_value(rotation))
+}
+
+#[cfg(feature = "3d")]
+pub(crate) fn to_rotation(rotation: Quat) -> Rotation {
+    Rotation(rotation)
+}
+
+/// Converts an angular velocity into a scaled axis.
+#[cfg(feature = "2d")]
+pub(crate) fn from_angular_value(angvel: AngularValue) -> Vec3 {
+    Vec3::Z * angvel
+}
+
+/// Converts an angular velocity into a scaled axis.
+#[cfg(feature = "3d")]
+pub(crate) fn from_angular_value(angvel: AngularValue) -> Vec3 {
+    angvel
+}
+
+/// Converts a scaled axis into an angular velocity.
+#[cfg(feature = "2d")]
+pub(crate) fn to_angular_value(scaled_axis: Vec3) -> AngularValue {
+    scaled_axis.z
+}
+
+/// Converts a scaled axis into an angular velocity.
+#[cfg(feature = "3d")]
+pub(crate) fn to_angular_value(scaled_axis: Vec3) -> AngularValue {
+    scaled_axis
+}
+
+#[cfg(feature = "2d")]
+pub(crate) fn clamp_angular_speed(angvel: AngularValue, max: Scalar) -> AngularValue {
+    angvel.clamp(-max, max)
+}
+
+#[cfg(feature = "3d")]
+pub(crate) fn clamp_angular_speed(angvel: AngularValue, max: Scalar) -> AngularValue {
+    angvel.clamp_length_max(max)
+}
+
+/// The direction an actor with the given rotation is looking at.
+/// In 2D, actors look along their local X axis.
+#[cfg(feature = "2d")]
+pub(crate) fn actor_forward(rotation: Quat) -> Dir3 {
+    Transform::from_rotation(rotation).right()
+}
+
+/// The direction an actor with the given rotation is looking at.
+#[cfg(feature = "3d")]
+pub(crate) fn actor_forward(rotation: Quat) -> Dir3 {
+    Transform::from_rotation(rotation).forward()
+}
+
+/// The direction perpendicular to [`actor_forward`] along which props are
+/// lined up. In 2D, this is the actor's local Y axis.
+#[cfg(feature = "2d")]
+pub(crate) fn actor_side(rotation: Quat) -> Dir3 {
+    Transform::from_rotation(rotation).up()
+}
+
+/// The direction perpendicular to [`actor_forward`] along which props are
+/// lined up.
+#[cfg(feature = "3d")]
+pub(crate) fn actor_side(rotation: Quat) -> Dir3 {
+    Transform::from_rotation(rotation).right()
+}
+
+/// Clamps the pitch of an actor's rotation, i.e. how far up or down it is
+/// looking. In 2D, the pitch is the angle between the actor's forward and the
+/// horizon it is facing, so actors looking to the left are clamped the same as
+/// actors looking to the right.
+#[cfg(feature = "2d")]
+pub(crate) fn clamp_actor_pitch(rotation: Quat, pitch_range: &RangeInclusive<f32>) -> Quat {
+    use std::f32::consts::PI;
+
+    let angle = rotation.to_euler(EulerRot::ZYX).0;
+    let facing_left = angle.cos() < 0.0;
+    let pitch = if facing_left {
+        (PI - angle + PI).rem_euclid(2.0 * PI) - PI
+    } else {
+        angle
+    };
+    let pitch = pitch.clamp(*pitch_range.start(), *pitch_range.end());
+    let angle = if facing_left { PI - pitch } else { pitch };
+    Quat::from_rotation_z(angle)
+}
+
+/// Clamps the pitch of an actor's rotation, i.e. how far up or down it is
+/// looking.
+#[cfg(feature = "3d")]
+pub(crate) fn clamp_actor_pitch(rotation: Quat, pitch_range: &RangeInclusive<f32>) -> Quat {
+    let (yaw, pitch, roll) = rotation.to_euler(EulerRot::YXZ);
+    let pitch = pitch.clamp(*pitch_range.start(), *pitch_range.end());
+    Quat::from_euler(EulerRot::YXZ, yaw, pitch, roll)
+}
+
+/// An axis-aligned box collider with the given half extent in every direction.
+#[cfg(feature = "2d")]
+pub(crate) fn box_collider(half_extent: Scalar) -> Collider {
+    Collider::rectangle(2.0 * half_extent, 2.0 * half_extent)
+}
+
+/// An axis-aligned box collider with the given half extent in every direction.
+#[cfg(feature = "3d")]
+pub(crate) fn box_collider(half_extent: Scalar) -> Collider {
+    Collider::cuboid(2.0 * half_extent, 2.0 * half_extent, 2.0 * half_extent)
+}
+
+/// A sphere collider with the given radius.
+#[cfg(feature = "2d")]
+pub(crate) fn sphere_collider(radius: Scalar) -> Collider {
+    Collider::circle(radius)
+}
+
+/// A sphere collider with the given radius.
+#[cfg(feature = "3d")]
+pub(crate) fn sphere_collider(radius: Scalar) -> Collider {
+    Collider::sphere(radius)
+}
+
 pub(crate) fn rigid_body_compound_collider(
     rigid_body: Entity,
     q_collider_ancestor: &Query<&Children, With<AncestorMarker<ColliderMarker>>>,
@@ -13,7 +238,7 @@ pub(crate) fn rigid_body_compound_collider(
     if let Ok((&_transform, col, layers)) = q_collider.get(rigid_body) {
         let layers = layers.copied().unwrap_or_default();
         if filter.test(rigid_body, layers) {
-            colliders.push((Vec3::ZERO, Quat::IDENTITY, col.clone()));
+            colliders.push((Vector::ZERO, to_rotation_value(Quat::IDENTITY), col.clone()));
         }
     }
     if let Ok(children) = q_collider_ancestor.get(rigid_body) {
@@ -39,12 +264,16 @@ fn rigid_body_compound_collider_recursive(
     q_collider_ancestor: &Query<&Children, With<AncestorMarker<ColliderMarker>>>,
     q_collider: &Query<(&Transform, &Collider, Option<&CollisionLayers>)>,
     filter: &SpatialQueryFilter,
-    colliders: &mut Vec<(Vec3, Quat, Collider)>,
+    colliders: &mut Vec<(Vector, RotationValue, Collider)>,
 ) {
     if let Ok((&transform, collider, layers)) = q_collider.get(candidate) {
         let layers = layers.copied().unwrap_or_default();
         if filter.test(candidate, layers) {
-            colliders.push((transform.translation, transform.rotation, collider.clone()));
+            colliders.push((
+                transform.translation.to_vector(),
+                to_rotation_value(transform.rotation),
+                collider.clone(),
+            ));
         }
     }
     if let Ok(children) = q_collider_ancestor.get(candidate) {
@@ -73,9 +302,62 @@ impl GetBestGlobalTransform
             .expect("Got an entity without `GlobalTransform`");
         if let Some(position) = position {
             if let Some(rotation) = rotation {
-                return Transform::from_translation(position.0).with_rotation(rotation.0);
+                return Transform::from_translation(position.0.to_vec3())
+                    .with_rotation(rotation.to_quat());
             }
         }
         global_transform.compute_transform()
     }
 }
+
+pub(crate) trait GetRigidBodyVelocity {
+    /// The linear velocity of the entity if it is a rigid body, otherwise
+    /// the one of its nearest rigid body ancestor.
+    /// Returns zero if there is none.
+    fn get_rigid_body_velocity(&self, entity: Entity) -> Vec3;
+}
+
+impl GetRigidBodyVelocity for Query<'_, '_, (Option<&LinearVelocity>, Option<&Parent>)> {
+    fn get_rigid_body_velocity(&self, entity: Entity) -> Vec3 {
+        let mut entity = entity;
+        while let Ok((velocity, parent)) = self.get(entity) {
+            if let Some(velocity) = velocity {
+                return velocity.0.to_vec3();
+            }
+            let Some(parent) = parent else {
+                break;
+            };
+            entity = parent.get();
+        }
+        Vec3::ZERO
+    }
+}
+
+#[cfg(all(test, feature = "2d"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clamp_actor_pitch_2d_mirrors_left_and_right() {
+        use std::f32::consts::{FRAC_PI_4, PI};
+
+        let range = -FRAC_PI_4..=FRAC_PI_4;
+        let clamped_angle = |angle: f32| {
+            let rotation = clamp_actor_pitch(Quat::from_rotation_z(angle), &range);
+            let direction = rotation * Vec3::X;
+            direction.y.atan2(direction.x)
+        };
+        let assert_close = |a: f32, b: f32| assert!((a - b).abs() < 1e-4, "{a} != {b}");
+
+        // Facing right
+        assert_close(clamped_angle(0.3), 0.3);
+        assert_close(clamped_angle(-0.3), -0.3);
+        assert_close(clamped_angle(1.2), FRAC_PI_4);
+        assert_close(clamped_angle(-1.2), -FRAC_PI_4);
+        // Facing left, looking up and down
+        assert_close(clamped_angle(PI - 0.3), PI - 0.3);
+        assert_close(clamped_angle(-PI + 0.3), -PI + 0.3);
+        assert_close(clamped_angle(PI - 1.2), PI - FRAC_PI_4);
+        assert_close(clamped_angle(-PI + 1.2), -PI + FRAC_PI_4);
+    }
+}
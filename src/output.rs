@@ -1,18 +1,45 @@
-//! Events related to props being thrown and dropped.
+//! Events related to props being held, thrown and dropped.
 //! These will be sent by the Avian Pickup plugin to notify the user of
 //! prop-related events. Handle these to e.g. play sound effects or show
 //! visual effects.
 
+use avian::math::Scalar;
+
 use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<(PropThrown, PropDropped)>()
-        .add_event::<PropThrown>()
-        .add_event::<PropDropped>();
+    app.register_type::<(
+        PropThrown,
+        PropDropped,
+        CoHolderJoined,
+        CoHolderLeft,
+        PropStolen,
+        HoldStrained,
+        ThrowCharging,
+        PropPunted,
+        PropAbsorbed,
+        CandidateChanged,
+        PickupDenied,
+        PickupDenialReason,
+    )>()
+    .add_event::<PropThrown>()
+    .add_event::<PropDropped>()
+    .add_event::<CoHolderJoined>()
+    .add_event::<CoHolderLeft>()
+    .add_event::<PropStolen>()
+    .add_event::<HoldStrained>()
+    .add_event::<ThrowCharging>()
+    .add_event::<PropPunted>()
+    .add_event::<PropAbsorbed>()
+    .add_event::<CandidateChanged>()
+    .add_event::<PickupDenied>();
 }
 
 pub(super) mod prelude {
-    pub use super::{PropDropped, PropThrown};
+    pub use super::{
+        CandidateChanged, CoHolderJoined, CoHolderLeft, HoldStrained, PickupDenialReason,
+        PickupDenied, PropAbsorbed, PropDropped, PropPunted, PropStolen, PropThrown, ThrowCharging,
+    };
 }
 
 /// Event sent when a prop is thrown by an actor.
@@ -52,3 +79,203 @@ pub struct PropDropped {
     /// volition.
     pub forced: bool,
 }
+
+/// Event sent when an actor starts holding a prop with
+/// [`CooperativeHold`](crate::prop::CooperativeHold) that is already being held
+/// by someone else.
+/// This is meant for the user to lister to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct CoHolderJoined {
+    /// The prop being held.
+    pub prop: Entity,
+    /// The actor that joined the hold.
+    pub actor: Entity,
+}
+
+/// Event sent when an actor stops holding a prop with
+/// [`CooperativeHold`](crate::prop::CooperativeHold) while others keep holding
+/// it. The actor will also send a [`PropThrown`] or [`PropDropped`] as usual.
+/// This is meant for the user to lister to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct CoHolderLeft {
+    /// The prop being held.
+    pub prop: Entity,
+    /// The actor that left the hold.
+    pub actor: Entity,
+}
+
+/// Event sent when an actor wins the contest over a prop held by another actor,
+/// as enabled by
+/// [`AvianPickupActorPullConfig::can_steal`](crate::prelude::AvianPickupActorPullConfig::can_steal).
+/// The actor the prop was stolen from will also send a forced [`PropDropped`].
+/// This is meant for the user to lister to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PropStolen {
+    /// The stolen prop.
+    pub prop: Entity,
+    /// The actor that was holding the prop.
+    pub from: Entity,
+    /// The actor that stole the prop.
+    pub to: Entity,
+}
+
+/// Event sent when a held prop is close to being forcefully dropped, i.e.
+/// when its [`HoldError::strain`](crate::prop::HoldError::strain) reaches
+/// [`AvianPickupActorHoldConfig::strained_fraction`](crate::prelude::AvianPickupActorHoldConfig::strained_fraction).
+/// Sent once per actor holding the prop. It is sent again only after the
+/// strain went back below the threshold.
+/// This is meant for the user to lister to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct HoldStrained {
+    /// The strained prop.
+    pub prop: Entity,
+    /// The actor holding the prop.
+    pub actor: Entity,
+}
+
+/// Event sent every update while an actor is charging a throw, as started by
+/// [`AvianPickupAction::StartChargingThrow`](crate::prelude::AvianPickupAction::StartChargingThrow).
+/// This is meant for the user to lister to in order to e.g. fill a UI bar.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ThrowCharging {
+    /// The actor charging the throw.
+    pub actor: Entity,
+    /// The prop that will be thrown.
+    pub prop: Entity,
+    /// How much of
+    /// [`AvianPickupActorThrowConfig::charge_duration`](crate::prelude::AvianPickupActorThrowConfig::charge_duration)
+    /// has passed, from 0.0 to 1.0.
+    pub fraction: Scalar,
+}
+
+/// Event sent when a prop is punted by an actor, as triggered by
+/// [`AvianPickupAction::Punt`](crate::prelude::AvianPickupAction::Punt).
+/// This is meant for the user to lister to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PropPunted {
+    /// The punted prop.
+    pub prop: Entity,
+    /// The actor that punted the prop.
+    pub actor: Entity,
+}
+
+/// Event sent when a prop is absorbed by an actor pulling with
+/// [`AvianPickupActorPullConfig::area`](crate::prelude::AvianPickupActorPullConfig::area).
+/// The prop is also added to the actor's
+/// [`AvianPickupActorAbsorbedProps`](crate::prelude::AvianPickupActorAbsorbedProps).
+/// This is meant for the user to lister to in order to play sound effects,
+/// despawn the prop, etc.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PropAbsorbed {
+    /// The absorbed prop.
+    pub prop: Entity,
+    /// The actor that absorbed the prop.
+    pub actor: Entity,
+}
+
+/// Event sent when the
+/// [`PickupCandidate`](crate::prelude::PickupCandidate) of an actor changes.
+/// This is meant for the user to lister to in order to e.g. highlight the prop.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct CandidateChanged {
+    /// The actor whose candidate changed.
+    pub actor: Entity,
+    /// The previous candidate prop, if any.
+    pub previous: Option<Entity>,
+    /// The new candidate prop, if any.
+    pub current: Option<Entity>,
+}
+
+/// Event sent when an actor is not allowed to pull, hold, throw or punt a prop,
+/// as decided by [`PickupRestriction`](crate::prelude::PickupRestriction) or
+/// [`AvianPickupVeto`](crate::prelude::AvianPickupVeto).
+/// This is meant for the user to lister to in order to e.g. show a message.
+/// Sending this has no effect on the prop itself.
+#[derive(Event, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PickupDenied {
+    /// The actor that was denied.
+    pub actor: Entity,
+    /// The prop the actor tried to interact with.
+    pub prop: Entity,
+    /// Why the actor was denied.
+    pub reason: PickupDenialReason,
+}
+
+/// Why a [`PickupDenied`] was sent.
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum PickupDenialReason {
+    /// The prop's [`PickupRestriction`](crate::prelude::PickupRestriction)
+    /// does not allow the actor.
+    Restricted,
+    /// The [`AvianPickupVeto`](crate::prelude::AvianPickupVeto) denied the
+    /// attempt with the given reason.
+    Vetoed(String),
+}
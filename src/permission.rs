@@ -0,0 +1,251 @@
+//! Module for vetoing pulls, holds, throws and punts of specific props.
+
+use avian::prelude::*;
+use bevy::{ecs::system::SystemId, prelude::*, utils::HashMap};
+
+use crate::{
+    prelude::{AvianPickupAction, Cooldown, PickupDenialReason, PickupDenied},
+    prop::PickupRestriction,
+    verb::Pulling,
+    AvianPickupSystem,
+};
+
+pub(super) mod prelude {
+    pub use super::{AvianPickupVeto, PickupAttempt, PickupAttemptKind};
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(PickupAttempt, PickupAttemptKind)>()
+        .add_systems(
+            PhysicsSchedule,
+            clear_permissions.in_set(AvianPickupSystem::ResetIdle),
+        );
+}
+
+/// Insert this resource to veto pulls, holds, throws and punts with your own
+/// rules, e.g. for props owned by another team or quest items the player can't
+/// carry yet. The system receives the [`PickupAttempt`] and returns `Some` reason
+/// to deny it or `None` to allow it. Denied attempts send
+/// [`PickupDenied`](crate::output::PickupDenied).
+///
+/// Every attempt is checked right before it happens, in the same physics step.
+/// While the actor keeps pulling, the veto is only asked once per prop and
+/// [`PickupAttemptKind`].
+///
+/// For simple cases, use [`PickupRestriction`](crate::prop::PickupRestriction)
+/// on the prop instead.
+///
+/// # Example
+///
+/// ```no_run
+/// # use avian_pickup::prelude::*;
+/// # use bevy::prelude::*;
+///
+/// #[derive(Component)]
+/// struct QuestItem;
+///
+/// fn veto(
+///     In(attempt): In<PickupAttempt>,
+///     q_quest_item: Query<(), With<QuestItem>>,
+/// ) -> Option<PickupDenialReason> {
+///     let is_quest_item = q_quest_item.contains(attempt.prop);
+///     (is_quest_item && attempt.kind != PickupAttemptKind::Pull)
+///         .then(|| PickupDenialReason::Vetoed("You can't carry this yet.".to_string()))
+/// }
+///
+/// let mut app = App::new();
+/// let veto = app.register_system(veto);
+/// app.insert_resource(AvianPickupVeto(veto));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct AvianPickupVeto(pub SystemId<PickupAttempt, Option<PickupDenialReason>>);
+
+/// The input of the [`AvianPickupVeto`] system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Debug, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PickupAttempt {
+    /// The actor trying to interact with the prop.
+    pub actor: Entity,
+    /// The rigid body entity of the prop.
+    pub prop: Entity,
+    /// What the actor is trying to do with the prop.
+    pub kind: PickupAttemptKind,
+}
+
+/// What an actor is trying to do in a [`PickupAttempt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Debug, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum PickupAttemptKind {
+    /// Pulling the prop towards the actor, including stealing it from other
+    /// actors.
+    Pull,
+    /// Picking up the prop, including absorbing it with
+    /// [`AvianPickupActorPullConfig::area`](crate::prelude::AvianPickupActorPullConfig::area).
+    Hold,
+    /// Throwing the held prop.
+    Throw,
+    /// Punting the prop with [`AvianPickupAction::Punt`].
+    Punt,
+}
+
+/// Caches the outcome of [`PickupAttempt`]s while an actor keeps pulling, so
+/// that [`AvianPickupVeto`] is not run and [`PickupDenied`] is not sent every
+/// physics step.
+#[derive(Debug, Clone, PartialEq, Eq, Component, Default)]
+pub(crate) struct PickupPermissions(HashMap<PickupAttempt, bool>);
+
+/// Runs `action` right after the current system if the attempt is allowed,
+/// so that a denied attempt has no effect at all. Unlike [`check_permission`],
+/// the outcome is cached while the actor keeps pulling.
+pub(crate) fn with_permission(
+    commands: &mut Commands,
+    attempt: PickupAttempt,
+    action: impl FnOnce(&mut World) + Send + 'static,
+) {
+    commands.add(move |world: &mut World| {
+        if check_cached_permission(world, attempt) {
+            action(world);
+        }
+    });
+}
+
+fn check_cached_permission(world: &mut World, attempt: PickupAttempt) -> bool {
+    let cached = world
+        .get::<PickupPermissions>(attempt.actor)
+        .and_then(|permissions| permissions.0.get(&attempt).copied());
+    if let Some(allowed) = cached {
+        return allowed;
+    }
+    let allowed = check_permission(world, attempt);
+    if let Some(mut permissions) = world.get_mut::<PickupPermissions>(attempt.actor) {
+        permissions.0.insert(attempt, allowed);
+    }
+    allowed
+}
+
+/// Returns whether the attempt is allowed by the prop's
+/// [`PickupRestriction`] and the [`AvianPickupVeto`], if any.
+/// Sends [`PickupDenied`] otherwise.
+pub(crate) fn check_permission(world: &mut World, attempt: PickupAttempt) -> bool {
+    let reason = if world
+        .get::<PickupRestriction>(attempt.prop)
+        .is_some_and(|restriction| !restriction.allows(attempt.actor))
+    {
+        Some(PickupDenialReason::Restricted)
+    } else if let Some(&AvianPickupVeto(veto)) = world.get_resource::<AvianPickupVeto>() {
+        world
+            .run_system_with_input(veto, attempt)
+            .unwrap_or_else(|error| {
+                error!("Failed to run `AvianPickupVeto`: {error}. Ignoring.");
+                None
+            })
+    } else {
+        None
+    };
+    let Some(reason) = reason else {
+        return true;
+    };
+    world.send_event(PickupDenied {
+        actor: attempt.actor,
+        prop: attempt.prop,
+        reason,
+    });
+    false
+}
+
+/// Forgets the cached permissions once the actor stops pulling, so that the
+/// next pull asks again.
+fn clear_permissions(mut q_actor: Query<(&mut PickupPermissions, Has<Pulling>, &Cooldown)>) {
+    for (mut permissions, has_pulling, cooldown) in q_actor.iter_mut() {
+        // Same logic as `flush_pulling_state`
+        if !permissions.0.is_empty() && !has_pulling && cooldown.finished(AvianPickupAction::Pull) {
+            permissions.0.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::{time::TimeUpdateStrategy, transform::TransformPlugin};
+
+    use super::*;
+    use crate::{
+        math::{actor_forward, sphere_collider},
+        prelude::*,
+    };
+
+    /// Pulls a prop in front of the actor and returns how many updates it took
+    /// until the prop was held.
+    fn updates_until_held(checked: bool) -> usize {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            PhysicsPlugins::default(),
+            AvianPickupPlugin,
+        ))
+        .init_resource::<Assets<Mesh>>()
+        .insert_resource(Gravity(default()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1.0 / 60.0,
+        )));
+        if checked {
+            let veto = app
+                .register_system(|In(_): In<PickupAttempt>| -> Option<PickupDenialReason> { None });
+            app.insert_resource(AvianPickupVeto(veto));
+        }
+
+        let actor = app
+            .world_mut()
+            .spawn((TransformBundle::default(), AvianPickupActor::default()))
+            .id();
+        let prop = app
+            .world_mut()
+            .spawn((
+                TransformBundle::from_transform(Transform::from_translation(
+                    actor_forward(Quat::IDENTITY) * 1.0,
+                )),
+                RigidBody::Dynamic,
+                sphere_collider(0.05),
+            ))
+            .id();
+        if checked {
+            app.world_mut()
+                .entity_mut(prop)
+                .insert(PickupRestriction::AllowedActors(vec![actor]));
+        }
+
+        for updates in 1..=60 {
+            app.world_mut().send_event(AvianPickupInput {
+                actor,
+                action: AvianPickupAction::Pull,
+            });
+            app.update();
+            let held_props = app.world().get::<AvianPickupActorHeldProps>(actor).unwrap();
+            if held_props.contains(prop) {
+                return updates;
+            }
+        }
+        panic!("The prop was never picked up");
+    }
+
+    #[test]
+    fn permission_checks_do_not_delay_pickups() {
+        assert_eq!(updates_until_held(true), updates_until_held(false));
+    }
+}
@@ -0,0 +1,262 @@
+//! Module for previewing what will happen when an actor throws a prop,
+//! e.g. to draw the trajectory of the throw.
+
+use avian::{math::Scalar, sync::ancestor_marker::AncestorMarker};
+use bevy::ecs::system::SystemParam;
+
+use crate::{
+    interaction::{intercept_velocity, launch_speed, target_motion},
+    math::{
+        actor_forward, rigid_body_compound_collider, to_dir, to_rotation_value,
+        GetBestGlobalTransform as _, GetRigidBodyVelocity as _, ToQuat as _, ToVec3 as _,
+        ToVector as _,
+    },
+    prelude::*,
+    prop::NonPickupRigidBody,
+    verb::ChargingThrow,
+};
+
+pub(super) mod prelude {
+    pub use super::{ThrowPreview, ThrowTrajectory, ThrowTrajectoryHit};
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(ThrowTrajectory, ThrowTrajectoryHit)>();
+}
+
+/// A [`SystemParam`] for simulating the throw an actor would perform if it
+/// received [`AvianPickupAction::Throw`] or [`AvianPickupAction::ThrowAt`]
+/// right now.
+///
+/// # Example
+/// ```
+/// # use avian_pickup::prelude::*;
+/// # use bevy::prelude::*;
+///
+/// fn print_landing_spot(preview: ThrowPreview, q_actor: Query<Entity, With<AvianPickupActor>>) {
+///     for actor in &q_actor {
+///         let Some(trajectory) = preview.trajectory(actor, 2.0, 64) else {
+///             continue;
+///         };
+///         if let Some(hit) = trajectory.hit {
+///             info!("The prop would land at {} after {} s", hit.position, hit.time);
+///         }
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct ThrowPreview<'w, 's> {
+    spatial_query: SpatialQuery<'w, 's>,
+    gravity: Res<'w, Gravity>,
+    q_actor: Query<
+        'w,
+        's,
+        (
+            &'static AvianPickupActor,
+            &'static AvianPickupActorState,
+            &'static AvianPickupActorHeldProps,
+            Option<&'static ChargingThrow>,
+        ),
+    >,
+    q_actor_transform: Query<
+        'w,
+        's,
+        (
+            &'static GlobalTransform,
+            Option<&'static Position>,
+            Option<&'static Rotation>,
+        ),
+    >,
+    q_actor_velocity: Query<'w, 's, (Option<&'static LinearVelocity>, Option<&'static Parent>)>,
+    q_target: Query<'w, 's, (&'static GlobalTransform, Option<&'static LinearVelocity>)>,
+    q_prop: Query<
+        'w,
+        's,
+        (
+            &'static Position,
+            &'static Rotation,
+            &'static Mass,
+            Option<&'static ThrownLinearSpeedOverride>,
+            Option<&'static LinearDamping>,
+            Option<&'static GravityScale>,
+            Option<&'static NonPickupRigidBody>,
+        ),
+    >,
+    q_collider_ancestor: Query<'w, 's, &'static Children, With<AncestorMarker<ColliderMarker>>>,
+    q_collider: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static Collider,
+            Option<&'static CollisionLayers>,
+        ),
+    >,
+}
+
+impl ThrowPreview<'_, '_> {
+    /// Simulates the throw of the prop the actor picked up most recently.
+    /// The trajectory is sampled `steps` times over `duration` seconds and
+    /// stops at the first obstacle, as determined by
+    /// [`AvianPickupActor::obstacle_filter`].\
+    /// Only gravity and linear damping are taken into account, so the actual
+    /// throw might differ if the prop collides with something on the way.\
+    /// Returns `None` if the actor is not holding anything or if the prop
+    /// goes back to being kinematic or static when thrown, see
+    /// [`DynamicWhileHeld`].
+    pub fn trajectory(
+        &self,
+        actor: Entity,
+        duration: Scalar,
+        steps: usize,
+    ) -> Option<ThrowTrajectory> {
+        self.simulate_throw(actor, None, duration, steps)
+    }
+
+    /// Like [`ThrowPreview::trajectory`], but for a throw with
+    /// [`AvianPickupAction::ThrowAt`] aimed at `target`.
+    pub fn trajectory_at(
+        &self,
+        actor: Entity,
+        target: AvianPickupThrowTarget,
+        duration: Scalar,
+        steps: usize,
+    ) -> Option<ThrowTrajectory> {
+        self.simulate_throw(actor, Some(target), duration, steps)
+    }
+
+    fn simulate_throw(
+        &self,
+        actor: Entity,
+        target: Option<AvianPickupThrowTarget>,
+        duration: Scalar,
+        steps: usize,
+    ) -> Option<ThrowTrajectory> {
+        let (config, state, held_props, charging) = self.q_actor.get(actor).ok()?;
+        let AvianPickupActorState::Holding(prop) = *state else {
+            return None;
+        };
+        let (
+            position,
+            rotation,
+            mass,
+            lin_speed_override,
+            damping,
+            gravity_scale,
+            non_pickup_rigid_body,
+        ) = self.q_prop.get(prop).ok()?;
+        if non_pickup_rigid_body.is_some_and(|rigid_body| rigid_body.0 != RigidBody::Dynamic) {
+            // The prop will not fly anywhere, see `DynamicWhileHeld`.
+            return None;
+        }
+        let actor_transform = self.q_actor_transform.get_best_global_transform(actor);
+
+        let speed = launch_speed(config, *mass, lin_speed_override, charging);
+        let inherited_velocity =
+            self.q_actor_velocity.get_rigid_body_velocity(actor) * config.velocity_inheritance;
+        let gravity_scale = gravity_scale.map_or(1.0, |scale| scale.0);
+        let gravity = self.gravity.0.to_vec3() * gravity_scale;
+        // Same aiming as the actual throw.
+        let aimed_velocity = target
+            .and_then(|target| target_motion(target, &self.q_target))
+            .and_then(|(target_position, target_velocity)| {
+                intercept_velocity(
+                    position.0.to_vec3(),
+                    target_position,
+                    target_velocity - inherited_velocity,
+                    speed,
+                    self.gravity.0.to_vec3(),
+                    gravity_scale,
+                )
+            });
+        let launch_velocity =
+            aimed_velocity.unwrap_or_else(|| actor_forward(actor_transform.rotation) * speed);
+        let mut velocity = launch_velocity + inherited_velocity;
+        let damping = damping.map_or(0.0, |damping| damping.0);
+
+        let collider = rigid_body_compound_collider(
+            prop,
+            &self.q_collider_ancestor,
+            &self.q_collider,
+            &config.prop_filter,
+        );
+        let mut filter = config.obstacle_filter.clone();
+        filter.excluded_entities.extend(held_props.iter());
+        let rotation = to_rotation_value(rotation.to_quat());
+
+        let steps = steps.max(1);
+        let dt = duration / steps as Scalar;
+        let mut position = position.0.to_vec3();
+        let mut points = Vec::with_capacity(steps + 1);
+        points.push(position);
+        for step in 1..=steps {
+            // Same integration as Avian uses.
+            velocity += gravity * dt;
+            velocity *= 1.0 / (1.0 + dt * damping);
+            let next_position = position + velocity * dt;
+            let delta = next_position - position;
+            if let (Some(collider), Ok(direction)) = (&collider, Dir3::new(delta)) {
+                let hit = self.spatial_query.cast_shape(
+                    collider,
+                    position.to_vector(),
+                    rotation,
+                    to_dir(direction),
+                    delta.length(),
+                    true,
+                    &filter,
+                );
+                if let Some(hit) = hit {
+                    let point = position + direction * hit.time_of_impact;
+                    points.push(point);
+                    let time = (step - 1) as Scalar * dt + dt * hit.time_of_impact / delta.length();
+                    return Some(ThrowTrajectory {
+                        points,
+                        hit: Some(ThrowTrajectoryHit {
+                            entity: hit.entity,
+                            position: point,
+                            time,
+                        }),
+                    });
+                }
+            }
+            position = next_position;
+            points.push(position);
+        }
+        Some(ThrowTrajectory { points, hit: None })
+    }
+}
+
+/// The result of [`ThrowPreview::trajectory`].
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ThrowTrajectory {
+    /// The sampled positions of the prop's center, starting at its current
+    /// position. If the prop hits an obstacle, the last point is where it
+    /// hits it.
+    pub points: Vec<Vec3>,
+    /// The first obstacle the prop would hit, if any.
+    pub hit: Option<ThrowTrajectoryHit>,
+}
+
+/// The first obstacle a thrown prop would hit, as predicted by
+/// [`ThrowPreview::trajectory`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ThrowTrajectoryHit {
+    /// The collider entity that would be hit.
+    pub entity: Entity,
+    /// The position of the prop's center when it hits the obstacle.
+    pub position: Vec3,
+    /// The time in seconds after the throw at which the obstacle is hit.
+    pub time: Scalar,
+}
@@ -2,7 +2,7 @@
 //! picked up or thrown. All of these are optional.
 use std::ops::RangeInclusive;
 
-use avian3d::math::Scalar;
+use avian::{math::Scalar, prelude::RigidBody};
 use bevy::prelude::*;
 
 use crate::prelude::AvianPickupActor;
@@ -14,15 +14,31 @@ pub(super) fn plugin(app: &mut App) {
         PreferredPickupDistanceOverride,
         PickupMassOverride,
         HeldProp,
+        AbsorbedProp,
+        CooperativeHold,
+        DynamicWhileHeld,
+        PickupRestriction,
+    )>()
+    .register_type::<(
         ThrownLinearSpeedOverride,
         ThrownAngularSpeedOverride,
+        ThrownSpinAxisOverride,
+        MaxHoldErrorOverride,
+        HoldErrorWarmupOverride,
+        MaxHoldErrorTimeOverride,
+        HoldStrainedFractionOverride,
     )>();
 }
 
+pub use crate::interaction::{HoldError, ShadowParams};
+
 pub(super) mod prelude {
     pub use super::{
-        HeldProp, PickupMassOverride, PitchRangeOverride, PreferredPickupDistanceOverride,
-        PreferredPickupRotation, ThrownAngularSpeedOverride, ThrownLinearSpeedOverride,
+        AbsorbedProp, CooperativeHold, DynamicWhileHeld, HeldProp, HoldError,
+        HoldErrorWarmupOverride, HoldStrainedFractionOverride, MaxHoldErrorOverride,
+        MaxHoldErrorTimeOverride, PickupMassOverride, PickupRestriction, PitchRangeOverride,
+        PreferredPickupDistanceOverride, PreferredPickupRotation, ShadowParams,
+        ThrownAngularSpeedOverride, ThrownLinearSpeedOverride, ThrownSpinAxisOverride,
     };
 }
 
@@ -43,9 +59,6 @@ pub(super) mod prelude {
 )]
 pub struct PreferredPickupRotation(pub Quat);
 
-#[derive(Debug, Clone, PartialEq, Component)]
-pub(crate) struct PrePickupRotation(pub Quat);
-
 /// Insert this on a prop to override
 /// [`AvianPickupActorHoldConfig::pitch_range`](crate::prelude::AvianPickupActorHoldConfig::pitch_range).
 #[derive(Debug, Clone, PartialEq, Component, Reflect)]
@@ -130,12 +143,114 @@ impl Default for ThrownAngularSpeedOverride {
     }
 }
 
+/// Insert this on a prop to make it always spin around the given axis in the
+/// actor's local space when thrown, as if
+/// [`AvianPickupActorThrowConfig::spin`](crate::prelude::AvianPickupActorThrowConfig::spin)
+/// was set to
+/// [`AvianPickupThrowSpin::Axis`](crate::prelude::AvianPickupThrowSpin::Axis).
+/// Combine with [`ThrownAngularSpeedOverride`] to also set the speed.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ThrownSpinAxisOverride(pub Vec3);
+
+impl Default for ThrownSpinAxisOverride {
+    /// Backspin around the actor's right vector in 3D,
+    /// counterclockwise spin in 2D.
+    fn default() -> Self {
+        #[cfg(feature = "2d")]
+        let axis = Vec3::Z;
+        #[cfg(feature = "3d")]
+        let axis = Vec3::X;
+        Self(axis)
+    }
+}
+
+/// Insert this on a prop to override
+/// [`AvianPickupActorHoldConfig::max_error`](crate::prelude::AvianPickupActorHoldConfig::max_error).
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct MaxHoldErrorOverride(pub Scalar);
+
+impl Default for MaxHoldErrorOverride {
+    fn default() -> Self {
+        Self(AvianPickupActor::default().hold.max_error)
+    }
+}
+
+/// Insert this on a prop to override
+/// [`AvianPickupActorHoldConfig::error_warmup`](crate::prelude::AvianPickupActorHoldConfig::error_warmup).
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct HoldErrorWarmupOverride(pub Scalar);
+
+impl Default for HoldErrorWarmupOverride {
+    fn default() -> Self {
+        Self(AvianPickupActor::default().hold.error_warmup)
+    }
+}
+
+/// Insert this on a prop to override
+/// [`AvianPickupActorHoldConfig::max_error_time`](crate::prelude::AvianPickupActorHoldConfig::max_error_time).
+/// Clamped to `0.0..=1.0` s as well.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct MaxHoldErrorTimeOverride(pub Scalar);
+
+impl Default for MaxHoldErrorTimeOverride {
+    fn default() -> Self {
+        Self(AvianPickupActor::default().hold.max_error_time)
+    }
+}
+
+/// Insert this on a prop to override
+/// [`AvianPickupActorHoldConfig::strained_fraction`](crate::prelude::AvianPickupActorHoldConfig::strained_fraction).
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct HoldStrainedFractionOverride(pub Scalar);
+
+impl Default for HoldStrainedFractionOverride {
+    fn default() -> Self {
+        Self(AvianPickupActor::default().hold.strained_fraction)
+    }
+}
+
 /// The cached mass that an object had before it was picked up
 /// that will be restored again when it is dropped.
 /// In other words, this is the mass before and after the pickup.
 #[derive(Debug, Clone, Copy, PartialEq, Component)]
 pub(crate) struct NonPickupMass(pub Scalar);
 
+/// The cached rigid body type that an object had before it was picked up
+/// that will be restored again when it is dropped.
+/// Only differs from [`RigidBody::Dynamic`] for props with [`DynamicWhileHeld`].
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub(crate) struct NonPickupRigidBody(pub RigidBody);
+
 /// Marker component for props that are held by an [`AvianPickupActor`].
 #[derive(Debug, Clone, Copy, PartialEq, Component, Hash, Default, Reflect)]
 #[reflect(Debug, Component, Default, Hash, PartialEq)]
@@ -145,3 +260,96 @@ pub(crate) struct NonPickupMass(pub Scalar);
     reflect(Serialize, Deserialize)
 )]
 pub struct HeldProp;
+
+/// Marker component for props that were absorbed by an [`AvianPickupActor`]
+/// pulling with
+/// [`AvianPickupActorPullConfig::area`](crate::prelude::AvianPickupActorPullConfig::area).
+/// Absorbed props are not pulled again. Remove this component to make them
+/// pullable again.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Hash, Default, Reflect)]
+#[reflect(Debug, Component, Default, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AbsorbedProp;
+
+/// Insert this on a prop to allow multiple [`AvianPickupActor`]s to hold it at
+/// the same time, e.g. to carry a couch together.
+///
+/// The prop is steered towards a blend of the targets of all actors holding
+/// it, weighted by their
+/// [`AvianPickupActorPullConfig::max_prop_mass`](crate::prelude::AvianPickupActorPullConfig::max_prop_mass).
+/// Their combined `max_prop_mass` is also used to determine whether they are
+/// strong enough to lift the prop together. Until then, actors can already
+/// grab the prop when close enough, but it will stay where it is.
+/// Once lifted, the prop is dropped if the remaining actors are no longer
+/// strong enough, e.g. because one of them let go.
+///
+/// Listen to [`CoHolderJoined`](crate::output::CoHolderJoined) and
+/// [`CoHolderLeft`](crate::output::CoHolderLeft) to react to actors joining or
+/// leaving the hold.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Hash, Default, Reflect)]
+#[reflect(Debug, Component, Default, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct CooperativeHold;
+
+/// Insert this on a [`RigidBody::Kinematic`] or [`RigidBody::Static`] prop to
+/// allow picking it up. Without it, only [`RigidBody::Dynamic`] props can be
+/// picked up.
+///
+/// The prop becomes [`RigidBody::Dynamic`] when picked up and goes back to its
+/// original rigid body type when dropped or thrown, e.g. for a door handle that
+/// pops off or a shelf item that stays in place until touched.\
+/// Since non-dynamic bodies are not moved by the physics engine, such a prop
+/// stays where it was released instead of falling down or flying away.
+/// This also means that it can't be thrown: throwing it releases it like a
+/// drop and sends [`PropDropped`](crate::output::PropDropped) instead of
+/// [`PropThrown`](crate::output::PropThrown).
+/// Such props can also not be pulled from afar, only picked up when within
+/// [`AvianPickupActor::interaction_distance`].
+#[derive(Debug, Clone, Copy, PartialEq, Component, Hash, Default, Reflect)]
+#[reflect(Debug, Component, Default, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DynamicWhileHeld;
+
+/// Insert this on a prop to restrict which [`AvianPickupActor`]s may pull,
+/// hold, throw or punt it, e.g. for a locked prop.
+/// Denied attempts send [`PickupDenied`](crate::output::PickupDenied) with
+/// [`PickupDenialReason::Restricted`](crate::output::PickupDenialReason::Restricted).\
+/// Actors are only matched by entity, not by tags or marker components.
+/// For such rules, or ones that depend on more than the actor, use
+/// [`AvianPickupVeto`](crate::permission::AvianPickupVeto).
+#[derive(Debug, Clone, PartialEq, Eq, Component, Default, Reflect)]
+#[reflect(Debug, Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum PickupRestriction {
+    /// No actor may interact with the prop.
+    #[default]
+    Locked,
+    /// Only the given actors may interact with the prop.
+    AllowedActors(Vec<Entity>),
+}
+
+impl PickupRestriction {
+    /// Whether the given actor may interact with the prop.
+    pub fn allows(&self, actor: Entity) -> bool {
+        match self {
+            Self::Locked => false,
+            Self::AllowedActors(actors) => actors.contains(&actor),
+        }
+    }
+}
@@ -3,6 +3,14 @@ use bevy::{
     prelude::*,
 };
 
+use crate::{
+    interaction::{AttachProp, DetachProp},
+    permission::check_permission,
+    prelude::{
+        AvianPickupActorHeldProps, AvianPickupThrowTarget, PickupAttempt, PickupAttemptKind,
+    },
+};
+
 pub(super) fn plugin(_app: &mut App) {}
 
 /// This marks a state transition coming from either
@@ -14,11 +22,16 @@ pub(super) fn plugin(_app: &mut App) {}
 /// is.
 ///
 /// This type itself is just an usher for the actual marker components.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum Verb {
-    /// Insert [`Throwing`] component and clear others
-    Throw(Entity),
-    /// Insert [`Dropping`] component and clear others
+    /// Detach the prop and add it to [`Throwing`], clear `Pulling`
+    Throw {
+        /// The prop to throw
+        prop: Entity,
+        /// Where to throw the prop. If `None`, it is thrown straight ahead.
+        target: Option<AvianPickupThrowTarget>,
+    },
+    /// Detach the prop and add it to [`Dropping`], clear `Pulling`
     Drop {
         /// The prop to drop
         prop: Entity,
@@ -26,17 +39,34 @@ pub(crate) enum Verb {
         /// being too far away from its target location.
         forced: bool,
     },
-    /// Insert [`Pulling`] component and clear others
+    /// Insert [`Pulling`] component
     Pull,
-    /// Insert [`Holding`] component and clear others
+    /// Attach the prop to the actor's held props, clear `Pulling`
     Hold(Entity),
+    /// Insert [`ChargingThrow`] and leave others untouched
+    ChargeThrow(Entity),
+    /// Insert [`Punting`] component, clear `Pulling`
+    Punt,
 }
 
-#[derive(Debug, Clone, Copy, Component)]
-pub(crate) struct Throwing(pub(crate) Entity);
+/// The props an actor let go of to throw them.
+/// They are already detached, so other verbs leave this untouched.
+#[derive(Debug, Clone, Default, Component)]
+pub(crate) struct Throwing(pub(crate) Vec<PendingThrow>);
 
-#[derive(Debug, Clone, Copy, Component)]
-pub(crate) struct Dropping {
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PendingThrow {
+    pub(crate) prop: Entity,
+    pub(crate) target: Option<AvianPickupThrowTarget>,
+}
+
+/// The props an actor let go of to drop them.
+/// They are already detached, so other verbs leave this untouched.
+#[derive(Debug, Clone, Default, Component)]
+pub(crate) struct Dropping(pub(crate) Vec<PendingDrop>);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PendingDrop {
     pub(crate) prop: Entity,
     pub(crate) forced: bool,
 }
@@ -45,10 +75,19 @@ pub(crate) struct Dropping {
 pub(crate) struct Pulling;
 
 #[derive(Debug, Clone, Copy, Component)]
-pub(crate) struct Holding(pub(crate) Entity);
+pub(crate) struct Punting;
+
+/// Lives on the actor while a throw is being charged.
+/// Used up by the next throw.
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct ChargingThrow {
+    pub(crate) prop: Entity,
+    /// Time in seconds since the charging started
+    pub(crate) elapsed: f32,
+}
 
 /// Sets or clears the [`Verb`] of an actor.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) struct SetVerb(pub(crate) Option<Verb>);
 
 impl SetVerb {
@@ -59,6 +98,20 @@ impl SetVerb {
 
 impl EntityCommand for SetVerb {
     fn apply(self, actor: Entity, world: &mut World) {
+        if let Some(Verb::Throw { prop, .. }) = self.0 {
+            let attempt = PickupAttempt {
+                actor,
+                prop,
+                kind: PickupAttemptKind::Throw,
+            };
+            if !check_permission(world, attempt) {
+                // Keep holding the prop, but don't keep charging the throw.
+                if let Some(mut actor) = world.get_entity_mut(actor) {
+                    actor.remove::<ChargingThrow>();
+                }
+                return;
+            }
+        }
         world.run_system_once_with((actor, self.0), set_verb);
     }
 }
@@ -66,74 +119,79 @@ impl EntityCommand for SetVerb {
 fn set_verb(
     In((actor, verb)): In<(Entity, Option<Verb>)>,
     mut commands: Commands,
-    q_actor: Query<(Has<Throwing>, Has<Dropping>, Has<Pulling>, Has<Holding>)>,
+    mut q_actor: Query<(
+        &AvianPickupActorHeldProps,
+        Option<&mut Throwing>,
+        Option<&mut Dropping>,
+        Has<Pulling>,
+        Has<ChargingThrow>,
+        Has<Punting>,
+    )>,
 ) {
-    let Ok((throwing, dropping, pulling, holding)) = q_actor.get(actor) else {
+    let Ok((held_props, throwing, dropping, pulling, charging, punting)) = q_actor.get_mut(actor)
+    else {
         error!("Actor entity was deleted or in an invalid state. Ignoring.");
         return;
     };
     let mut commands = commands.entity(actor);
     match verb {
-        Some(Verb::Throw(prop)) => {
-            if !throwing {
-                commands.insert(Throwing(prop));
-            }
-            if dropping {
-                commands.remove::<Dropping>();
+        Some(Verb::Throw { prop, target }) => {
+            // Several props can be released in the same update, but each only once.
+            if held_props.contains(prop) {
+                let throw = PendingThrow { prop, target };
+                if let Some(mut throwing) = throwing {
+                    throwing.0.push(throw);
+                } else {
+                    commands.insert(Throwing(vec![throw]));
+                }
+                commands.commands().trigger_targets(DetachProp(prop), actor);
             }
             if pulling {
                 commands.remove::<Pulling>();
             }
-            if holding {
-                commands.remove::<Holding>();
-            }
         }
         Some(Verb::Drop { prop, forced }) => {
-            if !dropping {
-                commands.insert(Dropping { prop, forced });
-            }
-            if throwing {
-                commands.remove::<Throwing>();
+            if held_props.contains(prop) {
+                let drop = PendingDrop { prop, forced };
+                if let Some(mut dropping) = dropping {
+                    dropping.0.push(drop);
+                } else {
+                    commands.insert(Dropping(vec![drop]));
+                }
+                commands.commands().trigger_targets(DetachProp(prop), actor);
             }
             if pulling {
                 commands.remove::<Pulling>();
             }
-            if holding {
-                commands.remove::<Holding>();
-            }
         }
         Some(Verb::Pull) => {
             if !pulling {
                 commands.insert(Pulling);
             }
-            if throwing {
-                commands.remove::<Throwing>();
-            }
-            if dropping {
-                commands.remove::<Dropping>();
-            }
-            if holding {
-                commands.remove::<Holding>();
-            }
         }
         Some(Verb::Hold(prop)) => {
-            if !holding {
-                commands.insert(Holding(prop));
+            commands.commands().trigger_targets(AttachProp(prop), actor);
+            if pulling {
+                commands.remove::<Pulling>();
             }
-            if throwing {
-                commands.remove::<Throwing>();
+        }
+        Some(Verb::ChargeThrow(prop)) => {
+            if !charging {
+                commands.insert(ChargingThrow { prop, elapsed: 0.0 });
             }
-            if dropping {
-                commands.remove::<Dropping>();
+        }
+        Some(Verb::Punt) => {
+            if !punting {
+                commands.insert(Punting);
             }
             if pulling {
                 commands.remove::<Pulling>();
             }
         }
         None => {
-            // `Throwing` and `Dropping` clean up after themselves.
-            // `Holding` should never be just removed, but only replaced by `Throwing` or
-            // `Dropping`. `Pulling` in the meantime should only be present
+            // `Throwing`, `Dropping` and `Punting` clean up after themselves.
+            // Held props are only ever released by `Throwing` or `Dropping`.
+            // `Pulling` in the meantime should only be present
             // while we are holding a button -> we can remove it here.
             if pulling {
                 commands.remove::<Pulling>();